@@ -0,0 +1,20 @@
+/**
+ * Types of asset that can be referenced by an {@link AssetReference}.
+ */
+export type AssetReferenceType = 'mesh' | 'texture' | 'sound' | 'script';
+
+/**
+ * A reference to an asset in the project.
+ * Use this as the type of a public field on a `ScriptComponent` to be able to choose the asset
+ * in the editor's inspector.
+ * @example
+ * ```typescript
+ *  export default class Spawner extends ScriptComponent {
+ *    public mesh: AssetReference<'mesh'> | undefined;
+ *  }
+ * ```
+ */
+export interface AssetReference<TAssetType extends AssetReferenceType = AssetReferenceType> {
+  readonly id: string;
+  readonly type: TAssetType;
+}
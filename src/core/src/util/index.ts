@@ -2,6 +2,7 @@ export * from './Vector2';
 export * from './Vector3';
 export * from './Color3';
 export * from './Color4';
+export * from './AssetReference';
 
 import { Vector2 } from './Vector2';
 import { Vector3 } from './Vector3';
@@ -1,120 +1,1136 @@
+pub mod cache;
+pub mod compiler;
+pub mod components;
+pub mod compression;
+pub mod core_modules;
+pub mod defines;
+pub mod dependencies;
+pub mod diagnostics;
+pub mod imports;
+pub mod manifest;
+pub mod properties;
+pub mod tree_shaking;
+pub mod tsconfig;
+pub mod variants;
+pub mod vendor;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::File;
-use std::io::{self, Read, Write};
-use std::path::Path;
+use std::io::{BufWriter, Read, Seek, Write};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
 use zip::write::SimpleFileOptions;
-use zip::ZipWriter;
-use swc::{config::{self, Config, Options, DecoratorVersion},Compiler};
-use swc_common::{errors::Handler, source_map::SourceMap, sync::Lrc, GLOBALS};
-use swc_ecma_ast::EsVersion;
-use swc_ecma_parser::{Syntax, TsSyntax};
+use zip::{ZipArchive, ZipWriter};
+use swc::{config::{self, Config, GlobalPassOption, JsMinifyOptions, Options, SourceMapsConfig},Compiler, JsMinifyExtras, TransformOutput};
+use swc_common::{errors::Handler, source_map::SourceMap, sync::Lrc, SourceFile, GLOBALS};
+use swc_ecma_ast::noop_pass;
+use swc_ecma_visit::{visit_mut_pass, VisitMutWith};
+use uuid::Uuid;
 
-/* @TODO remove all the printlns */
+use crate::cartridge::header::CartridgeHeader;
+use crate::cartridge::integrity;
+use crate::cartridge::manifest::{ArchiveEntryAlias, ArchiveEntryHash, CartridgeArchiveManifest, ComponentDefinition, GameObjectDefinition};
+use crate::cartridge::{CARTRIDGE_HEADER_FILENAME, CARTRIDGE_MANIFEST_FILENAME, CARTRIDGE_SIGNATURE_FILENAME};
+use crate::filesystem::assets::AssetType;
+use crate::filesystem::get_data_hash;
+use crate::filesystem::project::read_project_definition_from_path;
+use cache::BuildCache;
+use compiler::CompilerConfig;
+use components::ScriptClasses;
+use compression::CompressionPolicy;
+use diagnostics::{BuildDiagnostic, BuildError, DiagnosticCollector};
+use imports::{ImportRewriter, ScriptImport};
 
-pub fn build(
-    manifest_file_bytes: &[u8],
-    project_root_path: &str,
-    asset_paths: Vec<&str>,
-    script_paths: Vec<&str>,
-) -> Vec<u8> {
-    let mut zip_bytes = Vec::new();
-    let mut zip = ZipWriter::new(
-        std::io::Cursor::new(&mut zip_bytes)
-    );
+// Types
+/// Progress of a build. Reported after each file has been added to the cartridge.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildProgress {
+    /// Number of files processed so far
+    pub current: usize,
+    /// Total number of files in the cartridge
+    pub total: usize,
+    /// Path (within the cartridge) of the file that was just processed
+    pub path: String,
+}
+
+/// Which kind of cartridge to build
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuildProfile {
+    /// For debugging. Scripts are compiled with source maps, so that the web player's
+    /// devtools can show the original TypeScript.
+    #[default]
+    Debug,
+    /// For distributing the game. Scripts are minified (and comments removed).
+    Release,
+}
+
+/// Options controlling how a cartridge is built
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BuildOptions {
+    pub profile: BuildProfile,
+    /// Store the original source of each script in its source map. Only applies to debug builds.
+    pub embed_script_sources: bool,
+    /// Sign the cartridge with the ed25519 key stored in this file (see [`integrity::read_signing_key`])
+    pub signing_key_path: Option<PathBuf>,
+    /// Time (in seconds since the Unix epoch) recorded as when the cartridge was built, instead of the current time.
+    /// Builds of the same project with the same timestamp are byte-identical.
+    pub build_timestamp: Option<u64>,
+    /// Name of the build variant (see [`variants::BuildVariant`]) to build, instead of the whole project
+    pub variant: Option<String>,
+    /// How each kind of file is compressed. Read from the project file by [`build_project`].
+    #[serde(skip)]
+    pub compression: CompressionPolicy,
+    /// How scripts are compiled. Read from the project file by [`build_project`].
+    #[serde(skip)]
+    pub compiler: CompilerConfig,
+    /// Aliases that scripts can import each other by. Read from the project's `tsconfig.json` by [`build_project`].
+    #[serde(skip)]
+    pub path_aliases: tsconfig::PathAliases,
+    /// Global identifiers in scripts that are replaced with these values when they are compiled.
+    /// Set by [`build_project`] (see [`defines::get_defines`]).
+    #[serde(skip)]
+    pub defines: BTreeMap<String, serde_json::Value>,
+}
+
+impl BuildOptions {
+    /// Whether source maps are written into the cartridge next to each compiled script
+    pub fn source_maps(&self) -> bool {
+        self.profile == BuildProfile::Debug
+    }
+
+    /// Whether compiled scripts are minified
+    pub fn minify(&self) -> bool {
+        self.profile == BuildProfile::Release
+    }
+}
+
+/// The files describing a cartridge, written into it alongside the project's files
+#[derive(Debug, Clone)]
+pub struct CartridgeMetadata {
+    pub header: CartridgeHeader,
+    pub manifest: CartridgeArchiveManifest,
+}
+
+/// Summary of a finished build
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReport {
+    /// Total size (in bytes) of all compiled scripts, before minifying
+    pub script_size: u64,
+    /// Total size (in bytes) of all compiled scripts, after minifying. Only present if scripts were minified.
+    pub minified_script_size: Option<u64>,
+    /// Number of files not stored in the cartridge, because an identical file already was
+    pub duplicate_file_count: usize,
+    /// Total size (in bytes) of those files, uncompressed
+    pub duplicate_size: u64,
+    /// Paths of assets in the project that were left out of the cartridge, because nothing uses them
+    pub excluded_assets: Vec<PathBuf>,
+}
+
+impl fmt::Display for BuildReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.minified_script_size {
+            Some(minified_script_size) => {
+                let percentage = match self.script_size {
+                    0 => 100.0,
+                    script_size => minified_script_size as f64 / script_size as f64 * 100.0,
+                };
+                write!(f, "Scripts: {} bytes (minified from {} bytes, {:.1}%)", minified_script_size, self.script_size, percentage)?;
+            },
+            None => write!(f, "Scripts: {} bytes", self.script_size)?,
+        }
+        if self.duplicate_file_count > 0 {
+            write!(f, "\nDuplicates: {} file(s) stored once ({} bytes saved)", self.duplicate_file_count, self.duplicate_size)?;
+        }
+        if !self.excluded_assets.is_empty() {
+            write!(f, "\nExcluded {} unused asset(s):", self.excluded_assets.len())?;
+            for path in self.excluded_assets.iter() {
+                write!(f, "\n  {}", path.display())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Build a cartridge from the project on disk in `project_root` and write it to `output_path`.
+/// `project_file_path` is the project file in `project_root` to build (see [`crate::filesystem::project::find_project_file`]).
+/// The cartridge manifest is assembled from the project file and scene files on disk,
+/// so the result is the same whether the build is started from the editor or the CLI.
+/// The build itself runs on a pool of blocking threads, and can be stopped early using `cancellation_token`.
+pub async fn build_project(
+    project_root: &Path,
+    project_file_path: &Path,
+    entry_scene_id: Option<Uuid>,
+    output_path: &Path,
+    options: &BuildOptions,
+    on_progress: impl Fn(BuildProgress) + Send,
+    cancellation_token: CancellationToken,
+) -> Result<BuildReport, BuildError> {
+    // Read project definition from disk
+    let project_file_name = PathBuf::from(project_file_path.file_name().unwrap_or_default());
+    let project_definition = read_project_definition_from_path(project_file_path).await
+        .map_err(|error| BuildError::single(BuildDiagnostic::error(&project_file_name, error)))?;
+
+    // A variant can build a subset of the project, and override how it is built
+    let variant = match options.variant.as_deref() {
+        Some(variant_name) => {
+            log::info!("[build] Building variant: {}", variant_name);
+            Some((variant_name, variants::get_variant(&project_definition, variant_name, &project_file_name)?))
+        },
+        None => None,
+    };
+    let included_scene_ids = match variant {
+        Some((variant_name, variant)) => variants::get_included_scene_ids(&project_definition, variant_name, variant, &project_file_name)?,
+        None => None,
+    };
+    let profile = variant.and_then(|(_, variant)| variant.profile).unwrap_or(options.profile);
+    let options = BuildOptions {
+        profile,
+        compression: variant.and_then(|(_, variant)| variant.compression).unwrap_or(project_definition.compression),
+        compiler: project_definition.compiler.clone(),
+        path_aliases: tsconfig::PathAliases::read(project_root).map_err(BuildError::single)?,
+        defines: defines::get_defines(&project_definition, variant, profile, &project_file_name)?,
+        ..options.clone()
+    };
+    let variant = variant.map(|(_, variant)| variant);
+    options.compression.validate()
+        .map_err(|error| BuildError::single(BuildDiagnostic::error(&project_file_name, error)))?;
+    options.compiler.validate()
+        .map_err(|error| BuildError::single(BuildDiagnostic::error(&project_file_name, error)))?;
+    // @NOTE Only needed by tools that type-check scripts, so failing to write it does not fail the build
+    if let Err(error) = defines::write_declaration_file(project_root, &project_definition) {
+        log::warn!("[build] {}", error);
+    }
+
+    // Build cartridge header and manifest
+    let header = manifest::create_cartridge_header(&project_definition.manifest, &project_file_name, &options)?;
+    let mut manifest = manifest::create_cartridge_manifest(
+        project_root,
+        &project_definition,
+        &project_file_name,
+        entry_scene_id,
+        included_scene_ids.as_ref(),
+    ).await?;
+
+    // @NOTE Only assets that the game can use are packed
+    let include_assets: Vec<String> = project_definition.include_assets.iter()
+        .chain(variant.iter().flat_map(|variant| variant.include_assets.iter()))
+        .cloned()
+        .collect();
+    let used_asset_ids = tree_shaking::find_used_assets(
+        project_root,
+        &project_definition.assets,
+        &manifest.scenes,
+        &include_assets,
+        variant.map(|variant| variant.exclude_assets.as_slice()).unwrap_or_default(),
+        &options,
+        &project_file_name,
+    )?;
+    manifest.assets.retain(|asset| used_asset_ids.contains(&asset.id));
+    let (used_assets, unused_assets): (Vec<_>, Vec<_>) = project_definition.assets.iter()
+        .partition(|asset| used_asset_ids.contains(&asset.id));
+    for asset in unused_assets.iter() {
+        log::info!("[build] Excluding unused asset: {}", asset.path.display());
+    }
+
+    // Modules from npm packages that the scripts import are compiled along with them
+    let script_paths: Vec<&Path> = used_assets.iter()
+        .filter(|asset| AssetType::from_path(&asset.path) == AssetType::Script)
+        .map(|asset| asset.path.as_path())
+        .collect();
+    let vendor_file_paths = vendor::find_vendor_files(project_root, &script_paths, &options)?;
+    for vendor_file_path in vendor_file_paths.iter() {
+        log::info!("[build] Including module from npm package: {}", vendor_file_path);
+    }
+
+    let file_paths: Vec<String> = used_assets.iter()
+        .map(|asset| asset.path.to_str().unwrap().to_string())
+        .chain(vendor_file_paths)
+        .collect();
+
+    // @NOTE Write to a temporary file next to the output, so that a failed or cancelled
+    // build never leaves a half-written cartridge behind
+    let mut partial_output_file_name = output_path.file_name().unwrap_or_default().to_os_string();
+    partial_output_file_name.push(".partial");
+    let partial_output_path = output_path.with_file_name(partial_output_file_name);
+    let output_file = File::create(&partial_output_path).map_err(|error| {
+        BuildError::single(BuildDiagnostic::error(output_path, format!("Failed to create cartridge file: {error}")))
+    })?;
+
+    let result = build(
+        BufWriter::new(output_file),
+        CartridgeMetadata { header, manifest },
+        project_root,
+        file_paths,
+        options,
+        on_progress,
+        cancellation_token,
+    )
+        .await
+        .and_then(|(writer, report)| {
+            writer.into_inner().map_err(|error| {
+                BuildError::single(BuildDiagnostic::error(output_path, format!("Failed to write cartridge file: {error}")))
+            })?;
+            Ok(report)
+        });
+
+    match result {
+        Ok(report) => std::fs::rename(&partial_output_path, output_path)
+            .map(|_| BuildReport {
+                excluded_assets: unused_assets.iter().map(|asset| asset.path.clone()).collect(),
+                ..report
+            })
+            .map_err(|error| {
+                BuildError::single(BuildDiagnostic::error(output_path, format!("Failed to write cartridge file: {error}")))
+            }),
+        Err(error) => {
+            let _ = std::fs::remove_file(&partial_output_path);
+            Err(error)
+        }
+    }
+}
+
+/// Compile scripts and pack assets into a cartridge (zip archive), written to `writer`.
+/// `file_paths` are relative to `project_root`. Scripts are written to the cartridge first, followed by all other assets,
+/// each sorted by path (regardless of the order they are given in), so that building the same files always
+/// produces exactly the same cartridge.
+/// `on_progress` is called after every project file is added to the cartridge.
+/// Compiled and compressed files are cached in the project's [`cache::BUILD_CACHE_DIRECTORY`],
+/// so files that have not changed since the last build are copied into the cartridge as-is.
+/// Scripts attached to game objects in the manifest must be script components; the manifest is updated
+/// with metadata about each script component before it is written.
+/// Returns the writer, along with a summary of the build.
+pub async fn build<W: Write + Seek + Send + 'static>(
+    writer: W,
+    metadata: CartridgeMetadata,
+    project_root: &Path,
+    mut file_paths: Vec<String>,
+    options: BuildOptions,
+    on_progress: impl Fn(BuildProgress) + Send,
+    cancellation_token: CancellationToken,
+) -> Result<(W, BuildReport), BuildError> {
+    let CartridgeMetadata { header, mut manifest } = metadata;
+    file_paths.sort_by_cached_key(|file_path| {
+        (get_file_type(file_path) != AssetType::Script, file_path.replace('\\', "/"))
+    });
+    // @NOTE Read the signing key up-front, so that a missing key fails the build before any work is done
+    let signing_key = options.signing_key_path.as_deref()
+        .map(integrity::read_signing_key)
+        .transpose()
+        .map_err(|error| BuildError::single(BuildDiagnostic::error("", error)))?;
+    let compression = options.compression;
+
+    // Scripts + assets + manifest
+    let total = file_paths.len() + 1;
+    let mut current = 0;
+    let mut report_progress = move |path: &str| {
+        current += 1;
+        on_progress(BuildProgress {
+            current,
+            total,
+            path: path.to_string(),
+        });
+    };
+
+    // @NOTE Compilation is CPU-bound, so files are processed by a pool of workers on threads where
+    // blocking is acceptable. Each worker takes the next unprocessed file until there are none left.
+    let worker_count = std::thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1)
+        .clamp(1, file_paths.len().max(1));
+    let context = Arc::new(BuildWorkerContext {
+        project_root: project_root.to_path_buf(),
+        asset_paths: file_paths.iter().map(|file_path| file_path.replace('\\', "/")).collect(),
+        script_module_ids: file_paths.iter()
+            .filter(|file_path| AssetType::from_path(Path::new(file_path)) == AssetType::Script)
+            .filter_map(|file_path| imports::get_module_id(file_path))
+            .collect(),
+        file_paths,
+        next_file_index: AtomicUsize::new(0),
+        cache: BuildCache::new(project_root),
+        options,
+        cancellation_token: cancellation_token.clone(),
+    });
+
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let mut workers = JoinSet::new();
+    for _ in 0..worker_count {
+        let context = context.clone();
+        let sender = sender.clone();
+        workers.spawn_blocking(move || build_worker(&context, sender));
+    }
+    // @NOTE Drop the original sender so that the channel closes once every worker has finished
+    drop(sender);
+
+    // Collect results as workers finish each file
+    // @NOTE Results are stored by index, so that the cartridge is always written in the same order
+    // regardless of which worker finishes first
+    let mut results: Vec<Option<Result<CartridgeFiles, Vec<BuildDiagnostic>>>> = context.file_paths.iter().map(|_| None).collect();
+    while let Some((index, result)) = receiver.recv().await {
+        report_progress(&get_archive_path(&context.file_paths[index]));
+        results[index] = Some(result);
+    }
+
+    while let Some(worker_result) = workers.join_next().await {
+        worker_result.map_err(|error| {
+            BuildError::single(BuildDiagnostic::error("", format!("Build task failed: {error}")))
+        })?;
+    }
+
+    if cancellation_token.is_cancelled() {
+        return Err(BuildError::Cancelled);
+    }
+
+    // @NOTE Every problem is collected (rather than bailing on the first one)
+    // so that all broken files can be reported at once
+    let mut diagnostics = Vec::<BuildDiagnostic>::new();
+    if compression.uses_zstd() {
+        diagnostics.push(BuildDiagnostic::warning("", "Files compressed with zstd cannot be loaded by the web player yet"));
+    }
+    let mut files = Vec::new();
+    let mut report = BuildReport::default();
+    let mut script_imports = Vec::new();
+    let mut script_classes = Vec::new();
+    let mut asset_dependencies = Vec::new();
+    for (file_path, result) in context.file_paths.iter().zip(results.into_iter().flatten()) {
+        // @NOTE Modules from npm packages are compiled like scripts, but are not the project's scripts,
+        // so their imports and classes are not checked
+        let is_script = AssetType::from_path(Path::new(file_path)) == AssetType::Script && !vendor::is_vendor_path(file_path);
+        match result {
+            Ok(mut file) => {
+                if let Some(script) = file.script.take() {
+                    report.script_size += script.sizes.compiled;
+                    if let Some(minified) = script.sizes.minified {
+                        *report.minified_script_size.get_or_insert(0) += minified;
+                    }
+                    if is_script {
+                        script_imports.push((file_path.as_str(), script.imports));
+                        script_classes.push((file_path.as_str(), Some(script.classes)));
+                    }
+                }
+                if !file.dependencies.is_empty() {
+                    asset_dependencies.push((file_path.as_str(), std::mem::take(&mut file.dependencies)));
+                }
+                files.push(file);
+            },
+            Err(mut file_diagnostics) => {
+                if is_script {
+                    script_imports.push((file_path.as_str(), Vec::new()));
+                    script_classes.push((file_path.as_str(), None));
+                }
+                diagnostics.append(&mut file_diagnostics);
+            },
+        }
+    }
+
+    manifest.vendor_modules = context.file_paths.iter()
+        .filter(|file_path| vendor::is_vendor_path(file_path))
+        .map(|file_path| get_archive_path(&file_path.replace('\\', "/")))
+        .collect();
+
+    // @NOTE Scripts are compiled one at a time, so imports between them can only be checked once they have all been compiled
+    diagnostics.append(&mut imports::validate_imports(&script_imports, &context.options.path_aliases));
+    diagnostics.append(&mut add_script_components(&mut manifest, &script_classes));
+    add_asset_dependencies(&mut manifest, &asset_dependencies);
+
+    for diagnostic in diagnostics.iter().filter(|diagnostic| !diagnostic.is_error()) {
+        log::warn!("[build] {:?}: {}", diagnostic.path, diagnostic.message);
+    }
+
+    if diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+        return Err(BuildError::Failed { diagnostics });
+    }
+
+    // @NOTE Assets are listed in the same order no matter what order the project file lists them in
+    manifest.assets.sort_by(|a, b| a.path.cmp(&b.path).then(a.id.cmp(&b.id)));
+
+    // @NOTE Identical files are only stored once. Every other path with the same contents becomes an alias of the first.
+    manifest.aliases = find_duplicate_files(&files);
+    for alias in manifest.aliases.iter() {
+        log::debug!("[build] Storing {} as a duplicate of {}", alias.path, alias.target);
+    }
+    let alias_paths: HashSet<String> = manifest.aliases.iter().map(|alias| alias.path.clone()).collect();
+    let duplicate_hashes = files.iter()
+        .flat_map(|file| file.hashes.iter())
+        .filter(|hash| alias_paths.contains(&hash.path));
+    for hash in duplicate_hashes {
+        report.duplicate_file_count += 1;
+        report.duplicate_size += hash.size;
+    }
+
+    // Merge all files into the cartridge
+    let writer = tokio::task::spawn_blocking(move || {
+        let header_file_bytes = serde_json::to_vec(&header)
+            .map_err(|error| BuildError::single(BuildDiagnostic::error(CARTRIDGE_HEADER_FILENAME, format!("Failed to serialize header: {error}"))))?;
+
+        // @NOTE The manifest records the hash of every other file, so signing the manifest signs the whole cartridge
+        manifest.entries = std::iter::once(integrity::hash_entry(CARTRIDGE_HEADER_FILENAME, &header_file_bytes))
+            .chain(files.iter().flat_map(|file| file.hashes.iter().cloned()))
+            .filter(|hash| !alias_paths.contains(&hash.path))
+            .collect();
+        let manifest_file_bytes = serde_json::to_vec(&manifest)
+            .map_err(|error| BuildError::single(BuildDiagnostic::error(CARTRIDGE_MANIFEST_FILENAME, format!("Failed to serialize manifest: {error}"))))?;
+
+        let signature_file_bytes = signing_key
+            .map(|signing_key| {
+                log::info!("[build] Signing cartridge with key: {}", integrity::get_public_key(&signing_key));
+                serde_json::to_vec(&integrity::sign_manifest(&manifest_file_bytes, &signing_key))
+            })
+            .transpose()
+            .map_err(|error| BuildError::single(BuildDiagnostic::error(CARTRIDGE_SIGNATURE_FILENAME, format!("Failed to serialize signature: {error}"))))?;
+
+        write_cartridge(writer, &header_file_bytes, files, &manifest_file_bytes, signature_file_bytes.as_deref(), &alias_paths, &compression)
+    })
+        .await
+        .map_err(|error| BuildError::single(BuildDiagnostic::error("", format!("Build task failed: {error}"))))??;
+    report_progress(CARTRIDGE_MANIFEST_FILENAME);
+
+    log::info!("[build] {}", report);
+    Ok((writer, report))
+}
+
+/// State shared between all build workers
+struct BuildWorkerContext {
+    project_root: PathBuf,
+    /// Paths of every file to add to the cartridge, relative to `project_root`
+    file_paths: Vec<String>,
+    /// `file_paths`, using `/` as the separator. Meshes can only refer to these files.
+    asset_paths: HashSet<String>,
+    /// Module IDs of every script in `file_paths`. Aliases can only refer to these scripts.
+    script_module_ids: HashSet<String>,
+    /// Index (in `file_paths`) of the next file for a worker to process
+    next_file_index: AtomicUsize,
+    cache: BuildCache,
+    options: BuildOptions,
+    cancellation_token: CancellationToken,
+}
+
+/// The files produced from a single project file (e.g. a compiled script and its source map),
+/// ready to be written into the cartridge
+struct CartridgeFiles {
+    /// Paths of the files within the cartridge
+    archive_paths: Vec<String>,
+    data: CartridgeFilesData,
+    /// Hashes of each file's uncompressed contents (in the same order as `archive_paths`)
+    hashes: Vec<ArchiveEntryHash>,
+    /// Paths (relative to the project root) of other files this file refers to. Only present for meshes and materials.
+    dependencies: Vec<String>,
+    /// Only present for scripts
+    script: Option<ScriptMetadata>,
+}
+
+/// Information about a compiled script, which is checked against all other scripts once every file has been processed
+struct ScriptMetadata {
+    sizes: ScriptSizes,
+    /// Modules imported by the script
+    imports: Vec<ScriptImport>,
+    /// Classes declared by the script, used to check whether it is a script component
+    classes: ScriptClasses,
+}
+
+/// Size (in bytes) of a compiled script
+#[derive(Debug, Clone, Copy)]
+struct ScriptSizes {
+    compiled: u64,
+    /// Only present if the script was minified
+    minified: Option<u64>,
+}
 
-    let zip_options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+enum CartridgeFilesData {
+    /// Already compressed, as the files in a build cache entry (in the same order as `archive_paths`)
+    Cached(ZipArchive<File>),
+    /// Uncompressed contents of each file. Only used if the build cache could not be written.
+    Uncached(Vec<Vec<u8>>),
+}
 
+/// How a project file is built. Modules from npm packages are compiled like scripts.
+fn get_file_type(path: &str) -> AssetType {
+    match vendor::is_vendor_path(path) {
+        true => AssetType::Script,
+        false => AssetType::from_path(Path::new(path)),
+    }
+}
+
+/// Path of a project file within the cartridge. Scripts are renamed to `.js`.
+/// @NOTE Modules from npm packages keep their path, which is also their module ID
+fn get_archive_path(path: &str) -> String {
+    match AssetType::from_path(Path::new(path)) {
+        AssetType::Script => Path::new(path).with_extension("js").to_str().unwrap().to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// Compile / compress files from `context` until there are none left (or the build is cancelled),
+/// sending the result for each file (along with its index) to `sender`
+fn build_worker(
+    context: &BuildWorkerContext,
+    sender: mpsc::UnboundedSender<(usize, Result<CartridgeFiles, Vec<BuildDiagnostic>>)>,
+) {
     // @TODO could this just use a native js toolchain through a sidecar node binary?
     // https://tauri.app/v1/guides/building/sidecar/
 
-    // @NOTE Weird SWC "global" stuff
+    // @NOTE Weird SWC "global" stuff. Each worker has its own, as well as its own compiler.
     GLOBALS.set(&Default::default(), || {
         // SWC compilation setup
         // @NOTE I really don't know what "cm" is. Some kind of container for source files.
         let cm = Lrc::new(SourceMap::new(swc_common::FilePathMapping::empty()));
         let compiler = Compiler::new(cm.clone());
-        let handler = Handler::with_emitter_writer(Box::new(io::stderr()), Some(cm.clone()));
-
-         // @NOTE Same options as .swcrc: https://swc.rs/schema.json
-         let options = Options {
-            config: Config {
-                jsc: config::JscConfig {
-                    syntax: Some(Syntax::Typescript(TsSyntax {
-                        decorators: true,
-                        ..Default::default()
-                    })),
-                    target: Some(EsVersion::Es2016),
-                    transform: Some(config::TransformConfig {
-                        decorator_version: Some(DecoratorVersion::V202203),
-                        ..Default::default()
-                    }).into(),
-                    ..Default::default()
+        let script_options = get_compiler_options(&context.options);
+        let vendor_options = get_vendor_compiler_options(&context.options);
+        let minify_options = get_minify_options();
+
+        // @NOTE Compiled output depends on the compiler version and options, as well as the source
+        // @NOTE Only the options that affect compiled output are part of the key (e.g. not the signing key)
+        let script_cache_key = format!("{}\0{:?}\0{:?}", env!("CARGO_PKG_VERSION"), context.options.profile, script_options);
+        let vendor_cache_key = format!("{}\0{:?}\0{:?}", env!("CARGO_PKG_VERSION"), context.options.profile, vendor_options);
+
+        while !context.cancellation_token.is_cancelled() {
+            let index = context.next_file_index.fetch_add(1, Ordering::Relaxed);
+            let Some(file_path) = context.file_paths.get(index) else {
+                break;
+            };
+            let path = context.project_root.join(file_path);
+            let archive_path = get_archive_path(file_path);
+            // @NOTE Cached files are stored already compressed, so the compression setting is part of their key
+            let compression = context.options.compression.get_setting(&archive_path);
+            let zip_options = compression.zip_options();
+            let compression_cache_key = compression.cache_key();
+
+            let result = match get_file_type(file_path) {
+                /*
+                 * Compile scripts (and modules from npm packages) to JS using SWC
+                 */
+                AssetType::Script => {
+                    let is_vendor_module = vendor::is_vendor_path(file_path);
+                    let (options, syntax, compiler_cache_key) = match is_vendor_module {
+                        true => (&vendor_options, vendor::syntax(), &vendor_cache_key),
+                        false => (&script_options, context.options.compiler.script_syntax(&path), &script_cache_key),
+                    };
+                    let mut archive_paths = vec![archive_path.clone()];
+                    if context.options.source_maps() && !is_vendor_module {
+                        archive_paths.push(format!("{archive_path}.map"));
+                    }
+
+                    context.options.compiler.validate_script(&path)
+                        .map_err(|error| vec![BuildDiagnostic::error(file_path, error)])
+                        .and_then(|()| std::fs::read_to_string(&path)
+                            .map_err(|error| vec![BuildDiagnostic::error(file_path, format!("Failed to read file: {error}"))]))
+                        .and_then(|file_contents| {
+                            let file_hash = get_data_hash(file_contents.as_bytes());
+
+                            // Create in-memory source file from file contents
+                            let source = cm.new_source_file(
+                                swc_common::FileName::Real(path.clone()).into(),
+                                file_contents,
+                            );
+                            // @NOTE Imports and classes are checked against all other scripts once every script has been processed.
+                            // If the script cannot be parsed, the problem is reported when it is compiled.
+                            // @NOTE Imports that are aliases (see `tsconfig`) are rewritten to relative specifiers that `ScriptLoader`
+                            // can resolve, and imports of npm packages (see `vendor`) to the ID of the module they resolve to,
+                            // both here and when the script is compiled
+                            let (imports, classes, rewritten_imports) = match swc_ecma_parser::parse_file_as_module(
+                                &source,
+                                syntax,
+                                context.options.compiler.target,
+                                None,
+                                &mut Vec::new(),
+                            ) {
+                                Ok(mut module) => {
+                                    let mut imports = imports::find_imports(&cm, &module, syntax);
+                                    let mut rewritten_imports = match imports::get_module_id(file_path) {
+                                        Some(module_id) => imports::resolve_aliased_imports(
+                                            &imports,
+                                            &module_id,
+                                            &context.options.path_aliases,
+                                            |module_id| context.script_module_ids.contains(module_id),
+                                        ),
+                                        None => BTreeMap::new(),
+                                    };
+                                    let vendor_imports = vendor::resolve_vendor_imports(
+                                        &context.project_root,
+                                        file_path,
+                                        imports.iter()
+                                            .map(|import| import.specifier.as_str())
+                                            .filter(|specifier| !rewritten_imports.contains_key(*specifier)),
+                                    );
+                                    rewritten_imports.extend(vendor_imports);
+                                    module.visit_mut_with(&mut ImportRewriter::new(&rewritten_imports));
+                                    for import in imports.iter_mut() {
+                                        if let Some(specifier) = rewritten_imports.get(&import.specifier) {
+                                            import.specifier = specifier.clone();
+                                        }
+                                    }
+                                    (imports, components::find_classes(&cm, &module), rewritten_imports)
+                                },
+                                Err(_) => Default::default(),
+                            };
+
+                            // @NOTE Source maps refer to the script by name, so the path is part of the key too.
+                            // Rewritten imports depend on the other files in the build, so they are part of the key as well.
+                            let cache_key = BuildCache::key(&[
+                                "script",
+                                file_path,
+                                &file_hash,
+                                &format!("{rewritten_imports:?}"),
+                                compiler_cache_key,
+                                &compression_cache_key,
+                            ]);
+
+                            if let Some(mut entry) = context.cache.get(&cache_key) {
+                                log::debug!("[build] Using cached file: {}", archive_path);
+                                // @NOTE The size of the script before minifying is stored in the entry's metadata
+                                let size = entry.by_index_raw(0).map(|file| file.size()).unwrap_or_default();
+                                let compiled_size = std::str::from_utf8(entry.comment()).ok()
+                                    .and_then(|metadata| metadata.parse().ok())
+                                    .unwrap_or(size);
+                                let script_sizes = ScriptSizes {
+                                    compiled: compiled_size,
+                                    minified: context.options.minify().then_some(size),
+                                };
+                                return Ok((CartridgeFilesData::Cached(entry), ScriptMetadata { sizes: script_sizes, imports, classes }));
+                            }
+
+                            let output = compile_script(&compiler, &cm, options, &path, file_path, source, &rewritten_imports)?;
+                            let compiled_size = output.code.len() as u64;
+                            let output = match context.options.minify() {
+                                true => minify_script(&compiler, &cm, &minify_options, &path, file_path, output.code)?,
+                                false => output,
+                            };
+                            let script_sizes = ScriptSizes {
+                                compiled: compiled_size,
+                                minified: context.options.minify().then_some(output.code.len() as u64),
+                            };
+
+                            let mut files = vec![output.code.into_bytes()];
+                            files.extend(output.map.map(String::into_bytes));
+                            let data = cache_files(&context.cache, &cache_key, &archive_path, files, &compiled_size.to_string(), zip_options);
+                            Ok((data, ScriptMetadata { sizes: script_sizes, imports, classes }))
+                        })
+                        .map(|(data, script)| CartridgeFiles { archive_paths, data, hashes: Vec::new(), dependencies: Vec::new(), script: Some(script) })
                 },
-                module: Some(config::ModuleConfig::Amd(swc_ecma_transforms_module::amd::Config {
-                    ..Default::default()
-                })),
-                ..Default::default()
-            },
-            ..Default::default()
-        };
+                /*
+                 * Add other asset files as-is
+                 * @NOTE Except for references to other files within meshes and materials, which are normalised
+                 */
+                asset_type => {
+                    std::fs::read(&path)
+                        .map_err(|error| vec![BuildDiagnostic::error(file_path, format!("Failed to read file: {error}"))])
+                        .and_then(|buffer| match asset_type {
+                            AssetType::Mesh | AssetType::MeshSupplementary => {
+                                let mesh_file = dependencies::process_mesh_file(file_path, buffer, &context.asset_paths);
+                                match mesh_file.diagnostics.is_empty() {
+                                    true => Ok((mesh_file.contents, mesh_file.dependencies)),
+                                    false => Err(mesh_file.diagnostics),
+                                }
+                            },
+                            _ => Ok((buffer, Vec::new())),
+                        })
+                        .map(|(buffer, dependencies)| {
+                            // @NOTE Compressing large assets is slow, so the compressed data is cached too
+                            let cache_key = BuildCache::key(&["asset", &get_data_hash(&buffer), &compression_cache_key]);
+                            let data = match context.cache.get(&cache_key) {
+                                Some(entry) => {
+                                    log::debug!("[build] Using cached file: {}", archive_path);
+                                    CartridgeFilesData::Cached(entry)
+                                },
+                                None => cache_files(&context.cache, &cache_key, &archive_path, vec![buffer], "", zip_options),
+                            };
+                            CartridgeFiles { archive_paths: vec![archive_path], data, hashes: Vec::new(), dependencies, script: None }
+                        })
+                },
+            };
+            let result = result.and_then(|mut files| {
+                files.hashes = hash_files(&files.archive_paths, &mut files.data)
+                    .map_err(|error| vec![BuildDiagnostic::error(file_path, error)])?;
+                Ok(files)
+            });
 
-        /*
-         * Add script files to zip
-         * But first, compile them to JS using SWC
-         */
-        for script_path in script_paths {
-            let path = Path::new(project_root_path).join(script_path);
+            if sender.send((index, result)).is_err() {
+                // @NOTE Build has been abandoned
+                break;
+            }
+        }
+    });
+}
 
-            // Create in-memory source file from file on disk
-            let file_contents = std::fs::read_to_string(path.clone()).unwrap();
-            let source = cm.new_source_file(
-                swc_common::FileName::Real(path).into(),
-                file_contents,
-            );
+/// Record metadata about each script component in `manifest`.
+/// Returns an error for each script attached to a game object that is not a valid script component.
+/// `scripts` are the (project-relative) paths of every script in the build, with their classes
+/// (`None` if the script failed to compile).
+fn add_script_components(
+    manifest: &mut CartridgeArchiveManifest,
+    scripts: &[(&str, Option<ScriptClasses>)],
+) -> Vec<BuildDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    // Find every script attached to a game object, along with where it is first attached
+    let mut attached_scripts = HashMap::new();
+    for scene in manifest.scenes.iter() {
+        let mut objects: Vec<&GameObjectDefinition> = scene.objects.iter().collect();
+        while let Some(object) = objects.pop() {
+            for component in object.components.iter() {
+                if let ComponentDefinition::Script { script_file_id: Some(script_file_id), .. } = component {
+                    attached_scripts.entry(*script_file_id).or_insert((object.name.as_str(), scene.path.as_path()));
+                }
+            }
+            objects.extend(object.children());
+        }
+    }
+
+    // @NOTE Scripts that failed to compile have already been reported
+    let compiled_scripts: Vec<(&str, &ScriptClasses)> = scripts.iter()
+        .filter_map(|(script_path, classes)| classes.as_ref().map(|classes| (*script_path, classes)))
+        .collect();
+    for (script_index, (script_path, _)) in compiled_scripts.iter().enumerate() {
+        let archive_path = get_archive_path(script_path);
+        let Some(asset) = manifest.assets.iter_mut().find(|asset| asset.path == Path::new(&archive_path)) else {
+            continue;
+        };
+
+        match components::get_script_component(&compiled_scripts, script_index) {
+            Ok(script_component) => asset.script_component = Some(script_component),
+            Err(error) => {
+                // @NOTE Scripts that are not attached to anything (e.g. utilities) do not need to be script components
+                if let Some((object_name, scene_path)) = attached_scripts.get(&asset.id) {
+                    let message = format!(
+                        "Script attached to object '{}' in scene '{}' is not a valid script component: {}",
+                        object_name,
+                        scene_path.display(),
+                        error.message,
+                    );
+                    diagnostics.push(match &error.location {
+                        Some(location) => BuildDiagnostic::error_at(*script_path, location, message),
+                        None => BuildDiagnostic::error(*script_path, message),
+                    });
+                }
+            },
+        }
+    }
 
-            // Compile source file
-            let program = compiler
-                .process_js_file(source, &handler, &options)
-                .expect("Failed to process source file");
+    diagnostics
+}
 
-            // @NOTE Add compiled output to zip directly (do not write to disk)
+/// Record the assets each mesh (or material) depends on in `manifest`.
+/// `asset_dependencies` are the (project-relative) paths of each asset with dependencies, along with the paths of its dependencies.
+fn add_asset_dependencies(manifest: &mut CartridgeArchiveManifest, asset_dependencies: &[(&str, Vec<String>)]) {
+    let asset_ids_by_path: HashMap<String, Uuid> = manifest.assets.iter()
+        .map(|asset| (asset.path.to_string_lossy().replace('\\', "/"), asset.id))
+        .collect();
+    let dependencies_by_path: HashMap<String, &Vec<String>> = asset_dependencies.iter()
+        .map(|(path, dependencies)| (path.replace('\\', "/"), dependencies))
+        .collect();
 
-            // Rename to .js
-            let archive_path = Path::new(script_path).with_extension("js");
-            let archive_path_str = archive_path.to_str().unwrap();
+    for asset in manifest.assets.iter_mut() {
+        if let Some(dependencies) = dependencies_by_path.get(&asset.path.to_string_lossy().replace('\\', "/")) {
+            asset.dependencies = dependencies.iter()
+                .filter_map(|dependency_path| asset_ids_by_path.get(dependency_path).copied())
+                .collect();
+        }
+    }
+}
 
-            // Write to zip
-            zip.start_file(archive_path_str, zip_options).unwrap();
-            zip.write_all(program.code.as_bytes()).expect("Failed to zip data");
+/// Store processed files in the build cache so that future builds can reuse them.
+/// Falls back to keeping the file contents in memory if the cache is unavailable.
+fn cache_files(
+    cache: &BuildCache,
+    cache_key: &str,
+    archive_path: &str,
+    files: Vec<Vec<u8>>,
+    metadata: &str,
+    zip_options: SimpleFileOptions,
+) -> CartridgeFilesData {
+    let file_refs: Vec<&[u8]> = files.iter().map(Vec::as_slice).collect();
+    match cache.put(cache_key, &file_refs, metadata, zip_options) {
+        Ok(entry) => CartridgeFilesData::Cached(entry),
+        Err(error) => {
+            log::warn!("[build] Failed to write build cache entry for {}: {}", archive_path, error);
+            CartridgeFilesData::Uncached(files)
+        }
+    }
+}
 
-            println!("Added cartridge file: {}", archive_path_str);
+/// Find files with exactly the same contents as a file before them, returning an alias from each to the first such file
+fn find_duplicate_files(files: &[CartridgeFiles]) -> Vec<ArchiveEntryAlias> {
+    let mut first_paths = HashMap::<(&str, u64), &str>::new();
+    let mut aliases = Vec::new();
+    for hash in files.iter().flat_map(|file| file.hashes.iter()) {
+        // @NOTE SHA-256 (rather than xxh3) so that different files are never mistaken for each other
+        match first_paths.get(&(hash.sha256.as_str(), hash.size)) {
+            Some(target) => aliases.push(ArchiveEntryAlias {
+                path: hash.path.clone(),
+                target: target.to_string(),
+            }),
+            None => {
+                first_paths.insert((hash.sha256.as_str(), hash.size), hash.path.as_str());
+            },
         }
+    }
+    aliases
+}
 
-        /*
-         * Add asset files to zip directly
-         */
-        for asset_path in asset_paths {
-            let path = Path::new(project_root_path).join(asset_path);
+/// Hash the uncompressed contents of each processed file, to be recorded in the manifest
+fn hash_files(archive_paths: &[String], data: &mut CartridgeFilesData) -> Result<Vec<ArchiveEntryHash>, String> {
+    archive_paths.iter().enumerate()
+        .map(|(index, archive_path)| match data {
+            CartridgeFilesData::Cached(entry) => {
+                let mut buffer = Vec::new();
+                entry.by_index(index)
+                    .map_err(|error| error.to_string())
+                    .and_then(|mut cached_file| cached_file.read_to_end(&mut buffer).map_err(|error| error.to_string()))
+                    .map_err(|error| format!("Failed to read cached file: {error}"))?;
+                Ok(integrity::hash_entry(archive_path, &buffer))
+            },
+            CartridgeFilesData::Uncached(files) => Ok(integrity::hash_entry(archive_path, &files[index])),
+        })
+        .collect()
+}
 
-            // Read file data into buffer
-            let mut file = File::open(path).expect("Failed to open file");
-            let mut buffer = Vec::new();
-            file.read_to_end(&mut buffer).expect("Failed to read file");
+/// Write the header and processed files into a cartridge (zip archive), followed by the manifest (and its signature, if signed).
+/// Files in `alias_paths` are duplicates of other files, and are not written.
+fn write_cartridge<W: Write + Seek>(
+    writer: W,
+    header_file_bytes: &[u8],
+    files: Vec<CartridgeFiles>,
+    manifest_file_bytes: &[u8],
+    signature_file_bytes: Option<&[u8]>,
+    alias_paths: &HashSet<String>,
+    compression: &CompressionPolicy,
+) -> Result<W, BuildError> {
+    let mut zip = ZipWriter::new(writer);
 
-            // Write to zip
-            zip.start_file(asset_path, zip_options).unwrap();
-            zip.write_all(&buffer).expect("Failed to write file to zip");
+    /*
+     * Add header file to zip
+     * @NOTE The header is the first file, so tools can identify a cartridge by reading only the start of it
+     */
+    write_zip_file(&mut zip, CARTRIDGE_HEADER_FILENAME, header_file_bytes, compression.get_setting(CARTRIDGE_HEADER_FILENAME).zip_options())
+        .map_err(BuildError::single)?;
 
-            println!("Added cartridge file: {}", asset_path);
+    for mut files in files {
+        for (index, archive_path) in files.archive_paths.iter().enumerate() {
+            if alias_paths.contains(archive_path) {
+                continue;
+            }
+            let result = match &mut files.data {
+                // @NOTE Copy compressed data directly, without decompressing it
+                CartridgeFilesData::Cached(entry) => entry.by_index_raw(index)
+                    .and_then(|cached_file| zip.raw_copy_file_rename(cached_file, archive_path))
+                    .map_err(|error| BuildDiagnostic::error(archive_path, format!("Failed to write file to cartridge: {error}"))),
+                CartridgeFilesData::Uncached(data) => write_zip_file(&mut zip, archive_path, &data[index], compression.get_setting(archive_path).zip_options()),
+            };
+            result.map_err(BuildError::single)?;
+            log::debug!("[build] Added cartridge file: {}", archive_path);
         }
+    }
 
-        /*
-         * Add manifest file to zip
-         */
-        zip.start_file("manifest.json", zip_options).unwrap();
-        zip.write_all(manifest_file_bytes).expect("Failed to write manifest file");
-    });
+    /*
+     * Add manifest file to zip
+     */
+    write_zip_file(&mut zip, CARTRIDGE_MANIFEST_FILENAME, manifest_file_bytes, compression.get_setting(CARTRIDGE_MANIFEST_FILENAME).zip_options())
+        .map_err(BuildError::single)?;
+
+    /*
+     * Add signature file to zip
+     */
+    if let Some(signature_file_bytes) = signature_file_bytes {
+        write_zip_file(&mut zip, CARTRIDGE_SIGNATURE_FILENAME, signature_file_bytes, compression.get_setting(CARTRIDGE_SIGNATURE_FILENAME).zip_options())
+            .map_err(BuildError::single)?;
+    }
+
+    zip.finish().map_err(|error| {
+        BuildError::single(BuildDiagnostic::error("", format!("Failed to write cartridge: {error}")))
+    })
+}
+
+/// SWC options used to compile scripts
+fn get_compiler_options(build_options: &BuildOptions) -> Options {
+    // @NOTE Same options as .swcrc: https://swc.rs/schema.json
+    Options {
+        source_maps: Some(SourceMapsConfig::Bool(build_options.source_maps())),
+        config: Config {
+            inline_sources_content: build_options.embed_script_sources.into(),
+            jsc: config::JscConfig {
+                syntax: Some(build_options.compiler.syntax()),
+                target: Some(build_options.compiler.target),
+                transform: Some(config::TransformConfig {
+                    optimizer: get_optimizer_config(&build_options.defines),
+                    ..build_options.compiler.transform_config()
+                }).into(),
+                ..Default::default()
+            },
+            module: Some(build_options.compiler.module_config()),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+/// SWC options used to compile modules from npm packages.
+/// These are plain JavaScript, so the project's decorators and JSX settings do not apply.
+/// @NOTE Packages have their own source maps (if any), so none are written for their compiled modules
+fn get_vendor_compiler_options(build_options: &BuildOptions) -> Options {
+    let options = get_compiler_options(build_options);
+    Options {
+        source_maps: Some(SourceMapsConfig::Bool(false)),
+        config: Config {
+            inline_sources_content: false.into(),
+            jsc: config::JscConfig {
+                syntax: Some(vendor::syntax()),
+                transform: Some(config::TransformConfig {
+                    optimizer: get_optimizer_config(&build_options.defines),
+                    ..Default::default()
+                }).into(),
+                ..options.config.jsc
+            },
+            ..options.config
+        },
+        ..options
+    }
+}
+
+/// SWC options that replace each define with its value. `None` if there are no defines.
+/// @NOTE Branches that can never run once defines are replaced (e.g. `if (__DEBUG__) { ... }` in release builds)
+/// are removed when the script is minified
+fn get_optimizer_config(defines: &BTreeMap<String, serde_json::Value>) -> Option<config::OptimizerConfig> {
+    if defines.is_empty() {
+        return None;
+    }
+    // @NOTE Each value is parsed as a JS expression, so JSON literals can be used as-is
+    let vars: serde_json::Map<String, serde_json::Value> = defines.iter()
+        .map(|(name, value)| (name.clone(), serde_json::Value::String(value.to_string())))
+        .collect();
+    let globals: GlobalPassOption = serde_json::from_value(serde_json::json!({
+        "vars": vars,
+        // @NOTE By default, SWC also inlines environment variables of the editor (e.g. `process.env.NODE_ENV`)
+        "envs": [],
+    })).unwrap();
+    Some(config::OptimizerConfig {
+        globals: Some(globals),
+        // @NOTE Scripts are only simplified when minified
+        simplify: Some(config::SimplifyOption::Bool(false)),
+        ..Default::default()
+    })
+}
+
+/// SWC options used to minify compiled scripts
+fn get_minify_options() -> JsMinifyOptions {
+    // @NOTE Same options as `jsc.minify` in .swcrc: https://swc.rs/docs/configuration/minification
+    serde_json::from_value(serde_json::json!({
+        "compress": true,
+        "mangle": true,
+        "format": {
+            "comments": false,
+        },
+    })).unwrap()
+}
+
+/// Compile a single script file (already read from `path` into `source`) to JS. Any problems reported by SWC
+/// while compiling are returned as diagnostics.
+/// Imports in `rewritten_imports` are rewritten to the specifier they map to.
+fn compile_script(
+    compiler: &Compiler,
+    cm: &Lrc<SourceMap>,
+    options: &Options,
+    path: &Path,
+    script_path: &str,
+    source: Lrc<SourceFile>,
+    rewritten_imports: &BTreeMap<String, String>,
+) -> Result<TransformOutput, Vec<BuildDiagnostic>> {
+    // @NOTE Source maps are stored next to the compiled script, so refer to the original by file name only
+    let options = Options {
+        source_file_name: path.file_name().map(|file_name| file_name.to_string_lossy().to_string()),
+        ..options.clone()
+    };
+
+    // Compile source file
+    run_with_diagnostics(cm, script_path, "Failed to compile script", |handler| {
+        compiler.process_js_with_custom_pass(
+            source,
+            None,
+            handler,
+            &options,
+            Default::default(),
+            |_| visit_mut_pass(ImportRewriter::new(rewritten_imports)),
+            |_| noop_pass(),
+        )
+    })
+}
+
+/// Minify a single compiled script. `path` is the original script file on disk.
+fn minify_script(
+    compiler: &Compiler,
+    cm: &Lrc<SourceMap>,
+    options: &JsMinifyOptions,
+    path: &Path,
+    script_path: &str,
+    code: String,
+) -> Result<TransformOutput, Vec<BuildDiagnostic>> {
+    let source = cm.new_source_file(
+        swc_common::FileName::Real(path.with_extension("js")).into(),
+        code,
+    );
+
+    run_with_diagnostics(cm, script_path, "Failed to minify script", |handler| {
+        compiler.minify(source, handler, options, JsMinifyExtras::default())
+    })
+}
+
+/// Run an SWC operation on a single script. Any problems reported by SWC
+/// while running are returned as diagnostics.
+fn run_with_diagnostics<E: fmt::Display>(
+    cm: &Lrc<SourceMap>,
+    script_path: &str,
+    failure_message: &str,
+    operation: impl FnOnce(&Handler) -> Result<TransformOutput, E>,
+) -> Result<TransformOutput, Vec<BuildDiagnostic>> {
+    // Collect SWC's diagnostics for this file, instead of printing them
+    let collected_diagnostics = Arc::new(Mutex::new(Vec::new()));
+    let handler = Handler::with_emitter(
+        true,
+        false,
+        Box::new(DiagnosticCollector::new(Path::new(script_path), cm.clone(), collected_diagnostics.clone())),
+    );
+
+    let result = operation(&handler);
 
-    zip.finish().expect("Failed to write zip file");
+    let mut diagnostics = std::mem::take(&mut *collected_diagnostics.lock().unwrap());
+    match result {
+        Ok(output) if !handler.has_errors() => {
+            // @NOTE Warnings are not fatal, just log them
+            for diagnostic in diagnostics {
+                log::warn!("[build] {:?}: {}", diagnostic.path, diagnostic.message);
+            }
+            Ok(output)
+        },
+        Ok(_) => Err(diagnostics),
+        Err(error) => {
+            // Ensure the failure is reported, even if SWC did not emit anything through the handler
+            if !diagnostics.iter().any(|diagnostic| diagnostic.is_error()) {
+                diagnostics.push(BuildDiagnostic::error(script_path, format!("{failure_message}: {error}")));
+            }
+            Err(diagnostics)
+        },
+    }
+}
 
-    zip_bytes
-}
\ No newline at end of file
+/// Write a single file into the cartridge zip
+fn write_zip_file<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    archive_path: &str,
+    data: &[u8],
+    zip_options: SimpleFileOptions,
+) -> Result<(), BuildDiagnostic> {
+    zip.start_file(archive_path, zip_options)
+        .map_err(|error| error.to_string())
+        .and_then(|_| zip.write_all(data).map_err(|error| error.to_string()))
+        .map_err(|error| BuildDiagnostic::error(archive_path, format!("Failed to write file to cartridge: {error}")))
+}
@@ -0,0 +1,93 @@
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+use crate::filesystem::get_data_hash;
+
+// Constants
+/// Directory (relative to the project root) that build artifacts are cached in between builds
+pub const BUILD_CACHE_DIRECTORY: &str = ".polyzone/cache";
+/// Must be incremented whenever the way entries are written changes, so that older entries are not reused.
+/// Cached files are copied into cartridges as-is (including their metadata, such as modification times).
+const BUILD_CACHE_VERSION: &str = "2";
+
+/// Distinguishes the temporary files of entries being written by this process
+static NEXT_PARTIAL_ENTRY_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A persistent, content-addressed cache of processed cartridge entries (e.g. compiled scripts).
+/// Each entry is stored already-compressed as a zip archive, so that it can be copied
+/// into a cartridge as-is without being processed or compressed again.
+/// An entry can contain more than one file (e.g. a compiled script and its source map).
+pub struct BuildCache {
+    directory: PathBuf,
+}
+
+impl BuildCache {
+    pub fn new(project_root: &Path) -> Self {
+        Self {
+            directory: project_root.join(BUILD_CACHE_DIRECTORY),
+        }
+    }
+
+    /// Compute a cache key from everything that affects the contents of an entry
+    /// e.g. the hash of the source file and the options used to process it.
+    pub fn key(parts: &[&str]) -> String {
+        let parts: Vec<&str> = std::iter::once(BUILD_CACHE_VERSION).chain(parts.iter().copied()).collect();
+        get_data_hash(parts.join("\0").as_bytes())
+    }
+
+    /// Look up a previously-cached entry. The files in the returned archive are in the same order they were stored.
+    pub fn get(&self, key: &str) -> Option<ZipArchive<File>> {
+        let file = File::open(self.entry_path(key)).ok()?;
+        match ZipArchive::new(file) {
+            Ok(archive) if !archive.is_empty() => Some(archive),
+            _ => {
+                log::warn!("[BuildCache] (get) Ignoring corrupt cache entry: {}", key);
+                None
+            }
+        }
+    }
+
+    /// Compress `files` into a new cache entry. Returns the new entry, ready to be copied into a cartridge.
+    /// `metadata` is any extra information about the entry, which can be read back from the archive's comment.
+    pub fn put(&self, key: &str, files: &[&[u8]], metadata: &str, zip_options: SimpleFileOptions) -> io::Result<ZipArchive<File>> {
+        fs::create_dir_all(&self.directory)?;
+
+        // @NOTE Write to a temporary file first so that concurrent builds never observe a partially-written entry.
+        // Each writer has its own temporary file, as parallel workers (or builds) can write the same key at once
+        // (e.g. two assets with the same contents).
+        let entry_path = self.entry_path(key);
+        let partial_entry_id = NEXT_PARTIAL_ENTRY_ID.fetch_add(1, Ordering::Relaxed);
+        let partial_entry_path = self.directory.join(format!("{key}.{}.{partial_entry_id}.partial", std::process::id()));
+        let result = Self::write_entry(&partial_entry_path, files, metadata, zip_options)
+            .and_then(|()| fs::rename(&partial_entry_path, &entry_path));
+        if let Err(error) = result {
+            let _ = fs::remove_file(&partial_entry_path);
+            // @NOTE Entries with the same key have the same contents, so one stored by another writer can be used instead
+            if !entry_path.is_file() {
+                return Err(error);
+            }
+        }
+
+        Ok(ZipArchive::new(File::open(&entry_path)?)?)
+    }
+
+    /// Write the archive of an entry (see [`Self::put`]) to `path`
+    fn write_entry(path: &Path, files: &[&[u8]], metadata: &str, zip_options: SimpleFileOptions) -> io::Result<()> {
+        let mut zip = ZipWriter::new(File::create(path)?);
+        zip.set_comment(metadata);
+        for (index, data) in files.iter().enumerate() {
+            zip.start_file(index.to_string(), zip_options)?;
+            zip.write_all(data)?;
+        }
+        zip.finish()?;
+        Ok(())
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.directory.join(key).with_extension("zip")
+    }
+}
@@ -0,0 +1,150 @@
+use serde::Deserialize;
+use std::path::Path;
+use swc::config::{self, DecoratorVersion, ModuleConfig};
+use swc_ecma_ast::EsVersion;
+use swc_ecma_parser::{Syntax, TsSyntax};
+
+// Types
+/// How scripts are compiled.
+/// Read from the `compiler` section of the project file; any option that is not given uses its default.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CompilerConfig {
+    /// Version of JavaScript that scripts are compiled to (e.g. `es2016`, `es2022`).
+    /// Newer syntax than this is transformed into syntax this version supports.
+    pub target: EsVersion,
+    /// Format of the compiled modules
+    pub module: ModuleFormat,
+    /// Which proposal for decorators (if any) scripts are written in
+    pub decorators: DecoratorsSetting,
+    /// Allow JSX in scripts. Each element is compiled to a call to `jsx_factory`.
+    /// @NOTE Like TypeScript, JSX can only be used in `.tsx` scripts (and `.js` / `.jsx` scripts), as `.ts` scripts
+    /// can use angle-bracket type assertions (e.g. `<number>value`)
+    pub jsx: bool,
+    /// Function that JSX elements are compiled to calls of e.g. `h`. Required if `jsx` is enabled.
+    pub jsx_factory: Option<String>,
+    /// Component that JSX fragments (`<>...</>`) are compiled to e.g. `Fragment`
+    pub jsx_fragment_factory: Option<String>,
+}
+
+impl Default for CompilerConfig {
+    fn default() -> Self {
+        Self {
+            target: EsVersion::Es2016,
+            module: ModuleFormat::Amd,
+            decorators: DecoratorsSetting::V202203,
+            jsx: false,
+            jsx_factory: None,
+            jsx_fragment_factory: None,
+        }
+    }
+}
+
+/// Format of compiled script modules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModuleFormat {
+    /// Each script calls `define()` with its dependencies. Loaded by the web player's `ScriptLoader`.
+    /// @TODO Native ES modules, once `ScriptLoader` can load them
+    Amd,
+}
+
+/// Version of the decorators proposal that scripts are written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum DecoratorsSetting {
+    #[serde(rename = "2021-12")]
+    V202112,
+    #[serde(rename = "2022-03")]
+    V202203,
+    // @TODO 2023-11 once SWC supports it (it panics with "not yet implemented")
+    /// TypeScript's `experimentalDecorators`
+    #[serde(rename = "legacy")]
+    Legacy,
+    /// Decorators are a syntax error
+    #[serde(rename = "none")]
+    None,
+}
+
+impl CompilerConfig {
+    /// Syntax that scripts are compiled with.
+    /// @NOTE SWC adjusts this for each script based on its extension, in the same way as [`Self::script_syntax`]
+    pub fn syntax(&self) -> Syntax {
+        Syntax::Typescript(TsSyntax {
+            tsx: self.jsx,
+            decorators: self.decorators != DecoratorsSetting::None,
+            ..Default::default()
+        })
+    }
+
+    /// Syntax that a single script is parsed with
+    pub fn script_syntax(&self, script_path: &Path) -> Syntax {
+        let tsx = match script_path.extension().and_then(|extension| extension.to_str()) {
+            Some("ts") => false,
+            Some("tsx") => true,
+            _ => self.jsx,
+        };
+        Syntax::Typescript(TsSyntax {
+            tsx,
+            decorators: self.decorators != DecoratorsSetting::None,
+            ..Default::default()
+        })
+    }
+
+    /// SWC transform options for decorators and JSX
+    /// @NOTE Does not set `optimizer`, which depends on the build rather than the project
+    pub fn transform_config(&self) -> config::TransformConfig {
+        let decorator_version = match self.decorators {
+            DecoratorsSetting::V202112 => Some(DecoratorVersion::V202112),
+            DecoratorsSetting::V202203 => Some(DecoratorVersion::V202203),
+            DecoratorsSetting::Legacy | DecoratorsSetting::None => None,
+        };
+        // @NOTE Same options as `jsc.transform.react` in .swcrc: https://swc.rs/docs/configuration/compilation#jsctransformreact
+        let react = serde_json::from_value(serde_json::json!({
+            "runtime": "classic",
+            "pragma": self.jsx_factory,
+            "pragmaFrag": self.jsx_fragment_factory,
+        })).unwrap();
+        config::TransformConfig {
+            decorator_version,
+            legacy_decorator: (self.decorators == DecoratorsSetting::Legacy).into(),
+            react,
+            ..Default::default()
+        }
+    }
+
+    /// SWC options for the format of compiled modules
+    pub fn module_config(&self) -> ModuleConfig {
+        match self.module {
+            ModuleFormat::Amd => ModuleConfig::Amd(swc_ecma_transforms_module::amd::Config {
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// Check that the web player can load scripts compiled with these options
+    pub fn validate(&self) -> Result<(), String> {
+        // @NOTE Classes are compiled to functions before ES2015, which cannot extend the engine's classes (e.g. `ScriptComponent`)
+        if self.target < EsVersion::Es2015 {
+            return Err(format!("Invalid compiler target '{}': scripts must target es2015 or newer", get_target_name(self.target)));
+        }
+        if self.jsx && self.jsx_factory.is_none() {
+            // @NOTE Otherwise SWC compiles JSX to `React.createElement()`, which the web player does not provide
+            return Err(String::from("The compiler option `jsxFactory` must be set when `jsx` is enabled"));
+        }
+        Ok(())
+    }
+
+    /// Check that a script can be compiled with these options e.g. a `.tsx` script can only be compiled if `jsx` is enabled
+    pub fn validate_script(&self, script_path: &Path) -> Result<(), String> {
+        let extension = script_path.extension().and_then(|extension| extension.to_str());
+        if !self.jsx && matches!(extension, Some("tsx" | "jsx")) {
+            return Err(String::from("Cannot compile JSX script: the compiler option `jsx` is not enabled for this project"));
+        }
+        Ok(())
+    }
+}
+
+/// Name of a target in the project file e.g. `es2016`
+fn get_target_name(target: EsVersion) -> String {
+    format!("{target:?}").to_lowercase()
+}
@@ -0,0 +1,311 @@
+use std::collections::{HashMap, HashSet};
+use swc_common::source_map::SourceMap;
+use swc_ecma_ast::{
+    Class, ClassMember, Decl, DefaultDecl, ExportSpecifier, Expr, ImportSpecifier, Module, ModuleDecl, ModuleExportName, ModuleItem,
+    PropName, Stmt,
+};
+
+use super::core_modules::CORE_MODULES;
+use super::diagnostics::SourceLocation;
+use super::imports::{get_module_id, is_relative_specifier, resolve_relative_specifier};
+use super::properties::{get_class_properties, ScriptProperty, ScriptPropertySchema};
+use crate::cartridge::manifest::ScriptComponentClassDefinition;
+
+// Constants
+/// Name of the class that script components must extend
+const SCRIPT_COMPONENT_CLASS_NAME: &str = "ScriptComponent";
+/// Lifecycle methods that script components can override
+/// @NOTE Must match the methods of `GameObjectComponent` in: src/core/src/world/GameObjectComponent.ts
+const LIFECYCLE_METHODS: [&str; 3] = ["init", "onUpdate", "onDestroy"];
+/// Name used for the default export (and for an anonymous default-exported class)
+const DEFAULT_EXPORT_NAME: &str = "default";
+
+// Types
+/// A reference to a class, from the script it is used in (e.g. the class another class extends)
+#[derive(Debug, Clone)]
+enum ClassReference {
+    /// A class declared in the same script
+    Local(String),
+    /// A class imported from another module
+    Import { specifier: String, export_name: String },
+    /// Any other expression, which cannot be followed
+    Unknown,
+}
+
+/// A class declared at the top level of a script
+#[derive(Debug, Clone)]
+struct ScriptClass {
+    /// `None` if the class is anonymous
+    name: Option<String>,
+    super_class: Option<ClassReference>,
+    /// Names of the lifecycle methods declared by the class
+    lifecycle_methods: Vec<String>,
+    /// Fields declared by the class that can be edited in the inspector
+    properties: Vec<ScriptProperty>,
+    location: SourceLocation,
+}
+
+/// The classes declared and exported by a script.
+/// Used to check that a script is a valid script component, and to find the fields it has that can be edited in the inspector.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptClasses {
+    /// Classes declared at the top level of the script, by local name
+    classes: HashMap<String, ScriptClass>,
+    /// Local names of exported classes, by export name (`default` for the default export)
+    exports: HashMap<String, String>,
+}
+
+/// The reason a script is not a valid script component
+#[derive(Debug, Clone)]
+pub struct ScriptComponentError {
+    pub message: String,
+    /// Location of the problem within the script, if it has one
+    pub location: Option<SourceLocation>,
+}
+
+/// Find the classes declared and exported by a script
+pub fn find_classes(cm: &SourceMap, module: &Module) -> ScriptClasses {
+    let imports = get_import_bindings(module);
+
+    let mut script_classes = ScriptClasses::default();
+    let mut add_class = |local_name: String, name: Option<String>, class: &Class| {
+        let class = ScriptClass {
+            name,
+            super_class: class.super_class.as_deref().map(|super_class| get_class_reference(super_class, &imports)),
+            lifecycle_methods: get_lifecycle_methods(class),
+            properties: get_class_properties(class, &imports),
+            location: SourceLocation::new(cm, class.span.lo),
+        };
+        script_classes.classes.insert(local_name, class);
+    };
+    let mut exports = HashMap::new();
+
+    for item in module.body.iter() {
+        match item {
+            // e.g. `class Player extends ScriptComponent {}`
+            ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) => {
+                let name = class_decl.ident.sym.to_string();
+                add_class(name.clone(), Some(name), &class_decl.class);
+            },
+            // e.g. `export class Player extends ScriptComponent {}`
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                if let Decl::Class(class_decl) = &export_decl.decl {
+                    let name = class_decl.ident.sym.to_string();
+                    add_class(name.clone(), Some(name.clone()), &class_decl.class);
+                    exports.insert(name.clone(), name);
+                }
+            },
+            // e.g. `export default class Player extends ScriptComponent {}`
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default)) => {
+                if let DefaultDecl::Class(class_expr) = &export_default.decl {
+                    let name = class_expr.ident.as_ref().map(|ident| ident.sym.to_string());
+                    let local_name = name.clone().unwrap_or(DEFAULT_EXPORT_NAME.to_string());
+                    add_class(local_name.clone(), name, &class_expr.class);
+                    exports.insert(DEFAULT_EXPORT_NAME.to_string(), local_name);
+                }
+            },
+            // e.g. `export default Player;`
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default)) => {
+                if let Expr::Ident(ident) = &*export_default.expr {
+                    exports.insert(DEFAULT_EXPORT_NAME.to_string(), ident.sym.to_string());
+                }
+            },
+            // e.g. `export { Player, Player as default };`
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(named_export)) if named_export.src.is_none() => {
+                for specifier in named_export.specifiers.iter() {
+                    if let ExportSpecifier::Named(named) = specifier {
+                        let local_name = get_export_name(&named.orig);
+                        let export_name = named.exported.as_ref().map(get_export_name).unwrap_or(local_name.clone());
+                        exports.insert(export_name, local_name);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+
+    script_classes.exports = exports;
+    script_classes
+}
+
+impl ScriptClasses {
+    /// The class the script default-exports (with its local name), if it declares one
+    fn get_default_export(&self) -> Option<(&str, &ScriptClass)> {
+        let local_name = self.exports.get(DEFAULT_EXPORT_NAME)?;
+        Some((local_name, self.classes.get(local_name)?))
+    }
+
+    /// The fields of the script's default-exported class that can be edited in the inspector (see `properties`).
+    /// Empty if the script does not default-export a class.
+    pub fn get_property_schema(&self) -> ScriptPropertySchema {
+        ScriptPropertySchema {
+            properties: self.get_default_export().map(|(_, class)| class.properties.clone()).unwrap_or_default(),
+        }
+    }
+}
+
+/// Check whether a script default-exports a class extending `ScriptComponent` (directly, or through other classes).
+/// `scripts` are the (project-relative) paths of every script in the build, with their classes,
+/// so that classes extending classes from other scripts can be followed.
+pub fn get_script_component(
+    scripts: &[(&str, &ScriptClasses)],
+    script_index: usize,
+) -> Result<ScriptComponentClassDefinition, ScriptComponentError> {
+    let script_classes = scripts[script_index].1;
+    let Some((local_name, class)) = script_classes.get_default_export() else {
+        return Err(ScriptComponentError {
+            message: String::from("Script does not default-export a class"),
+            location: None,
+        });
+    };
+
+    let mut lifecycle_methods = Vec::new();
+    let mut visited = HashSet::new();
+    match extends_script_component(scripts, script_index, local_name, &mut lifecycle_methods, &mut visited) {
+        true => {
+            // @NOTE Sorted so that the manifest does not depend on the order of the class hierarchy
+            lifecycle_methods.sort_by_key(|method| LIFECYCLE_METHODS.iter().position(|lifecycle_method| lifecycle_method == method));
+            Ok(ScriptComponentClassDefinition {
+                class_name: class.name.clone(),
+                lifecycle_methods,
+            })
+        },
+        false => Err(ScriptComponentError {
+            message: format!(
+                "Default-exported class '{}' does not extend {SCRIPT_COMPONENT_CLASS_NAME}",
+                class.name.as_deref().unwrap_or(DEFAULT_EXPORT_NAME),
+            ),
+            location: Some(class.location.clone()),
+        }),
+    }
+}
+
+/// Follow the class hierarchy of the class named `local_name` in the script at `script_index` until reaching `ScriptComponent`.
+/// Lifecycle methods declared along the way are added to `lifecycle_methods`.
+/// `visited` are the classes already followed, by script index and local name.
+fn extends_script_component(
+    scripts: &[(&str, &ScriptClasses)],
+    script_index: usize,
+    local_name: &str,
+    lifecycle_methods: &mut Vec<String>,
+    visited: &mut HashSet<(usize, String)>,
+) -> bool {
+    // @NOTE Guard against cycles e.g. classes extending each other across scripts
+    if !visited.insert((script_index, local_name.to_string())) {
+        return false;
+    }
+    let Some(class) = scripts[script_index].1.classes.get(local_name) else {
+        return false;
+    };
+
+    for method in class.lifecycle_methods.iter() {
+        if !lifecycle_methods.contains(method) {
+            lifecycle_methods.push(method.clone());
+        }
+    }
+
+    let (super_class_script_index, super_class_local_name) = match &class.super_class {
+        Some(ClassReference::Local(name)) => (script_index, name.as_str()),
+        Some(ClassReference::Import { specifier, export_name }) => {
+            if CORE_MODULES.contains(&specifier.as_str()) {
+                return export_name == SCRIPT_COMPONENT_CLASS_NAME;
+            }
+
+            // Find the class exported from the other script
+            let Some(super_class_script_index) = get_module_id(scripts[script_index].0)
+                .filter(|_| is_relative_specifier(specifier))
+                .and_then(|module_id| resolve_relative_specifier(&module_id, specifier))
+                .and_then(|module_id| scripts.iter().position(|(script_path, _)| get_module_id(script_path).as_ref() == Some(&module_id)))
+            else {
+                return false;
+            };
+            let Some(local_name) = scripts[super_class_script_index].1.exports.get(export_name) else {
+                return false;
+            };
+            (super_class_script_index, local_name.as_str())
+        },
+        Some(ClassReference::Unknown) | None => return false,
+    };
+
+    extends_script_component(scripts, super_class_script_index, super_class_local_name, lifecycle_methods, visited)
+}
+
+/// Find the local names of every imported binding in a script e.g. `import { ScriptComponent as Base } from '...'`.
+/// Returns the module specifier and export name of each binding, by local name.
+/// Namespace imports (e.g. `import * as World from '...'`) have the export name `*`.
+pub fn get_import_bindings(module: &Module) -> HashMap<String, (String, String)> {
+    let mut imports = HashMap::new();
+    for item in module.body.iter() {
+        let ModuleItem::ModuleDecl(ModuleDecl::Import(import)) = item else {
+            continue;
+        };
+        for specifier in import.specifiers.iter() {
+            let (local, export_name) = match specifier {
+                ImportSpecifier::Named(named) => {
+                    let export_name = match &named.imported {
+                        Some(imported) => get_export_name(imported),
+                        None => named.local.sym.to_string(),
+                    };
+                    (named.local.sym.to_string(), export_name)
+                },
+                ImportSpecifier::Default(default) => (default.local.sym.to_string(), DEFAULT_EXPORT_NAME.to_string()),
+                // @NOTE Namespace imports are only used through member expressions e.g. `World.ScriptComponent`
+                ImportSpecifier::Namespace(namespace) => (namespace.local.sym.to_string(), String::from("*")),
+            };
+            imports.insert(local, (import.src.value.to_string(), export_name));
+        }
+    }
+    imports
+}
+
+/// Resolve the expression after `extends` in a class declaration
+fn get_class_reference(expr: &Expr, imports: &HashMap<String, (String, String)>) -> ClassReference {
+    match expr {
+        // e.g. `extends ScriptComponent`
+        Expr::Ident(ident) => match imports.get(ident.sym.as_str()) {
+            Some((specifier, export_name)) => ClassReference::Import {
+                specifier: specifier.clone(),
+                export_name: export_name.clone(),
+            },
+            None => ClassReference::Local(ident.sym.to_string()),
+        },
+        // e.g. `extends World.ScriptComponent` (from `import * as World from '@polyzone/core/world'`)
+        Expr::Member(member) => {
+            let (Expr::Ident(object), Some(property)) = (&*member.obj, member.prop.as_ident()) else {
+                return ClassReference::Unknown;
+            };
+            match imports.get(object.sym.as_str()) {
+                Some((specifier, export_name)) if export_name == "*" => ClassReference::Import {
+                    specifier: specifier.clone(),
+                    export_name: property.sym.to_string(),
+                },
+                _ => ClassReference::Unknown,
+            }
+        },
+        Expr::Paren(paren) => get_class_reference(&paren.expr, imports),
+        _ => ClassReference::Unknown,
+    }
+}
+
+/// Names of the (non-static) lifecycle methods declared in a class
+fn get_lifecycle_methods(class: &Class) -> Vec<String> {
+    class.body.iter()
+        .filter_map(|member| match member {
+            ClassMember::Method(method) if !method.is_static => match &method.key {
+                PropName::Ident(ident) => Some(ident.sym.to_string()),
+                PropName::Str(name) => Some(name.value.to_string()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .filter(|name| LIFECYCLE_METHODS.contains(&name.as_str()))
+        .collect()
+}
+
+fn get_export_name(name: &ModuleExportName) -> String {
+    match name {
+        ModuleExportName::Ident(ident) => ident.sym.to_string(),
+        ModuleExportName::Str(name) => name.value.to_string(),
+    }
+}
+
@@ -0,0 +1,115 @@
+use serde::Deserialize;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, DateTime};
+
+use crate::cartridge::{CARTRIDGE_HEADER_FILENAME, CARTRIDGE_MANIFEST_FILENAME, CARTRIDGE_SIGNATURE_FILENAME};
+
+// Types
+/// How files in a cartridge are compressed, based on what kind of file they are.
+/// Read from the `compression` section of the project file; any category that is not given uses its default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CompressionPolicy {
+    /// Files in formats that are already compressed (e.g. `.png`, `.ogg`). Stored by default,
+    /// as compressing them again makes almost no difference to their size.
+    pub precompressed: CompressionSetting,
+    /// Meshes (e.g. `.obj`, `.gltf`) and their supplementary files (e.g. `.mtl`)
+    pub meshes: CompressionSetting,
+    /// Compiled scripts and source maps, as well as the cartridge's manifest and header
+    pub text: CompressionSetting,
+    /// Any other files
+    pub other: CompressionSetting,
+}
+
+impl Default for CompressionPolicy {
+    fn default() -> Self {
+        Self {
+            precompressed: CompressionSetting::Stored,
+            meshes: CompressionSetting::Deflate { level: None },
+            text: CompressionSetting::Deflate { level: None },
+            other: CompressionSetting::Deflate { level: None },
+        }
+    }
+}
+
+/// How a single category of files is compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(tag = "method", rename_all = "camelCase")]
+pub enum CompressionSetting {
+    /// Not compressed
+    Stored,
+    /// `level` is from 0 (fastest) to 9 (smallest). Defaults to 6.
+    Deflate { level: Option<i64> },
+    /// `level` is from -7 (fastest) to 22 (smallest). Defaults to 3.
+    /// @NOTE The web player cannot load zstd-compressed files (yet)
+    Zstd { level: Option<i64> },
+}
+
+impl CompressionPolicy {
+    /// Compression setting for a file, based on its path within the cartridge
+    pub fn get_setting(&self, archive_path: &str) -> CompressionSetting {
+        if [CARTRIDGE_HEADER_FILENAME, CARTRIDGE_MANIFEST_FILENAME, CARTRIDGE_SIGNATURE_FILENAME].contains(&archive_path) {
+            return self.text;
+        }
+
+        let extension = Path::new(archive_path).extension().and_then(|extension| extension.to_str());
+        match extension {
+            Some("png" | "jpg" | "jpeg" | "basis" | "webp" | "mp3" | "ogg" | "glb") => self.precompressed,
+            Some("obj" | "fbx" | "gltf" | "stl" | "mtl" | "bin") => self.meshes,
+            Some("js" | "mjs" | "ts" | "tsx" | "jsx" | "map" | "json" | "pzscene") => self.text,
+            _ => self.other,
+        }
+    }
+
+    /// Check that every compression level is within range for its method
+    pub fn validate(&self) -> Result<(), String> {
+        for (category, setting) in [
+            ("precompressed", self.precompressed),
+            ("meshes", self.meshes),
+            ("text", self.text),
+            ("other", self.other),
+        ] {
+            let (method, range, level) = match setting {
+                CompressionSetting::Stored => continue,
+                CompressionSetting::Deflate { level } => ("deflate", 0..=9, level),
+                CompressionSetting::Zstd { level } => ("zstd", -7..=22, level),
+            };
+            if let Some(level) = level.filter(|level| !range.contains(level)) {
+                return Err(format!(
+                    "Invalid compression level for '{}': {} (must be between {} and {} for {})",
+                    category, level, range.start(), range.end(), method,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether any category of files is compressed with zstd
+    pub fn uses_zstd(&self) -> bool {
+        [self.precompressed, self.meshes, self.text, self.other].iter()
+            .any(|setting| matches!(setting, CompressionSetting::Zstd { .. }))
+    }
+}
+
+impl CompressionSetting {
+    /// Options for writing a file into a zip archive with this setting
+    pub fn zip_options(&self) -> SimpleFileOptions {
+        let (method, level) = match *self {
+            CompressionSetting::Stored => (CompressionMethod::Stored, None),
+            CompressionSetting::Deflate { level } => (CompressionMethod::Deflated, level),
+            CompressionSetting::Zstd { level } => (CompressionMethod::Zstd, level),
+        };
+        // @NOTE Every file has the same (zip's earliest possible) modification time, rather than the time it was written,
+        // so that building the same project always produces exactly the same cartridge
+        SimpleFileOptions::default()
+            .compression_method(method)
+            .compression_level(level)
+            .last_modified_time(DateTime::default())
+    }
+
+    /// Identifies this setting within build cache keys, as cached files are stored already compressed
+    pub fn cache_key(&self) -> String {
+        format!("{:?}", self)
+    }
+}
@@ -0,0 +1,35 @@
+/* *******************
+ * AUTO-GENERATED FILE
+ * *******************
+ * @NOTE This file is automatically generated from the contents of `@polyzone/core`
+ * To regenerate this file, run `npm run build:core.g` in the runtime project
+ *
+ * DO NOT EDIT THIS FILE MANUALLY, AS ANY CHANGES TO IT WILL BE OVERWRITTEN.
+ */
+
+/// Names of all the core modules that can be imported by scripts
+pub const CORE_MODULES: [&str; 23] = [
+    "@polyzone/core",
+    "@polyzone/core/world/GameObject",
+    "@polyzone/core/world/GameObjectComponent",
+    "@polyzone/core/world/Transform",
+    "@polyzone/core/world",
+    "@polyzone/core/world/components/CameraComponent",
+    "@polyzone/core/world/components/DirectionalLightComponent",
+    "@polyzone/core/world/components/MeshComponent",
+    "@polyzone/core/world/components/PointLightComponent",
+    "@polyzone/core/world/components/ScriptComponent",
+    "@polyzone/core/world/components",
+    "@polyzone/core/util/AssetReference",
+    "@polyzone/core/util/Color3",
+    "@polyzone/core/util/Color4",
+    "@polyzone/core/util/Vector2",
+    "@polyzone/core/util/Vector3",
+    "@polyzone/core/util",
+    "@polyzone/core/modules/IModule",
+    "@polyzone/core/modules/Input",
+    "@polyzone/core/modules",
+    "@polyzone/core/modules/World/WorldModule",
+    "@polyzone/core/modules/World/WorldQuery",
+    "@polyzone/core/modules/World",
+];
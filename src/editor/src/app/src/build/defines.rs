@@ -0,0 +1,118 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::Path;
+
+use crate::filesystem::project::ProjectDefinition;
+use super::diagnostics::{BuildDiagnostic, BuildError};
+use super::variants::BuildVariant;
+use super::BuildProfile;
+
+// Constants
+/// Defines that every build has, which cannot be defined by projects
+pub const BUILT_IN_DEFINES: [(&str, &str); 3] = [
+    // (Name, TypeScript type)
+    ("__DEBUG__", "boolean"),
+    ("__VARIANT__", "string | null"),
+    ("__GAME_VERSION__", "string | null"),
+];
+/// Path (relative to the project root) of the file declaring every define, so that scripts using them type-check
+/// @NOTE Inside `.polyzone` so that it is never picked up as a script asset
+pub const DEFINES_DECLARATION_FILE: &str = ".polyzone/defines.d.ts";
+
+/// Values of every define for a build: the built-in defines, followed by the project's (overridden by the variant's, if any).
+/// Each define is a global identifier in scripts that is replaced with its value when they are compiled.
+pub fn get_defines(
+    project_definition: &ProjectDefinition,
+    variant: Option<(&str, &BuildVariant)>,
+    profile: BuildProfile,
+    project_file_name: &Path,
+) -> Result<BTreeMap<String, Value>, BuildError> {
+    let mut diagnostics = Vec::new();
+    validate_defines(&project_definition.defines, "`defines`", project_file_name, &mut diagnostics);
+    if let Some((variant_name, variant)) = variant {
+        validate_defines(&variant.defines, &format!("`defines` of variant '{variant_name}'"), project_file_name, &mut diagnostics);
+    }
+    if !diagnostics.is_empty() {
+        return Err(BuildError::Failed { diagnostics });
+    }
+
+    let mut defines: BTreeMap<String, Value> = project_definition.defines.clone().into_iter()
+        .chain(variant.into_iter().flat_map(|(_, variant)| variant.defines.clone()))
+        .collect();
+    defines.insert("__DEBUG__".to_string(), Value::Bool(profile == BuildProfile::Debug));
+    defines.insert("__VARIANT__".to_string(), variant.map(|(variant_name, _)| Value::from(variant_name)).unwrap_or(Value::Null));
+    defines.insert("__GAME_VERSION__".to_string(), project_definition.manifest.version.clone().map(Value::from).unwrap_or(Value::Null));
+    Ok(defines)
+}
+
+/// Write a declaration for every define the project (or any of its variants) can have to [`DEFINES_DECLARATION_FILE`].
+/// The file is only written if its contents have changed.
+pub fn write_declaration_file(project_root: &Path, project_definition: &ProjectDefinition) -> Result<(), String> {
+    // @NOTE Types are collected from every variant, so that the declarations are the same whichever variant is built
+    let mut types = BTreeMap::<&str, BTreeSet<&str>>::new();
+    let all_defines = std::iter::once(&project_definition.defines)
+        .chain(project_definition.variants.values().map(|variant| &variant.defines));
+    for defines in all_defines {
+        for (name, value) in defines.iter() {
+            if let Some(define_type) = get_typescript_type(value) {
+                types.entry(name.as_str()).or_default().insert(define_type);
+            }
+        }
+    }
+
+    let mut contents = String::from("// Generated by PolyZone when building cartridges. Do not edit.\n");
+    contents.push_str("// Each of these is replaced with its value when scripts are compiled (see `defines` in the project file).\n\n");
+    for (name, define_type) in BUILT_IN_DEFINES {
+        contents.push_str(&format!("declare const {name}: {define_type};\n"));
+    }
+    for (name, define_types) in types.iter().filter(|(name, _)| !is_built_in(name)) {
+        contents.push_str(&format!("declare const {name}: {};\n", define_types.iter().copied().collect::<Vec<_>>().join(" | ")));
+    }
+
+    let path = project_root.join(DEFINES_DECLARATION_FILE);
+    if std::fs::read_to_string(&path).is_ok_and(|existing_contents| existing_contents == contents) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|error| format!("Failed to write {DEFINES_DECLARATION_FILE}: {error}"))?;
+    }
+    std::fs::write(&path, contents).map_err(|error| format!("Failed to write {DEFINES_DECLARATION_FILE}: {error}"))
+}
+
+/// Check that every define has a valid name and a value that can be substituted into scripts.
+/// `source` describes where in the project file the defines are from.
+fn validate_defines(defines: &HashMap<String, Value>, source: &str, project_file_name: &Path, diagnostics: &mut Vec<BuildDiagnostic>) {
+    let mut names: Vec<&String> = defines.keys().collect();
+    names.sort();
+    for name in names {
+        if is_built_in(name) {
+            diagnostics.push(BuildDiagnostic::error(project_file_name, format!("'{name}' in {source} is defined by every build, so cannot be overridden")));
+        } else if !is_identifier(name) {
+            diagnostics.push(BuildDiagnostic::error(project_file_name, format!("'{name}' in {source} is not a valid identifier")));
+        } else if get_typescript_type(&defines[name]).is_none() {
+            diagnostics.push(BuildDiagnostic::error(project_file_name, format!("'{name}' in {source} must be a boolean, number, string or null")));
+        }
+    }
+}
+
+/// TypeScript type of a define's value. `None` if the value cannot be a define (i.e. arrays and objects).
+fn get_typescript_type(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::Null => Some("null"),
+        Value::Bool(_) => Some("boolean"),
+        Value::Number(_) => Some("number"),
+        Value::String(_) => Some("string"),
+        Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+fn is_built_in(name: &str) -> bool {
+    BUILT_IN_DEFINES.iter().any(|(built_in_name, _)| *built_in_name == name)
+}
+
+/// Whether `name` can be used as a global identifier in scripts e.g. `__MY_DEFINE__`
+fn is_identifier(name: &str) -> bool {
+    let mut characters = name.chars();
+    characters.next().is_some_and(|character| character.is_ascii_alphabetic() || character == '_' || character == '$')
+        && characters.all(|character| character.is_ascii_alphanumeric() || character == '_' || character == '$')
+}
@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+use std::path::Path;
+use serde_json::Value;
+
+use super::diagnostics::{BuildDiagnostic, SourceLocation};
+use super::imports::get_relative_specifier;
+use super::tsconfig::normalize_path;
+
+// Constants
+/// Statements in `.mtl` files that refer to a texture (as well as any statement starting with `map_`)
+const MTL_TEXTURE_STATEMENTS: [&str; 5] = ["bump", "disp", "decal", "refl", "norm"];
+/// Options of texture statements in `.mtl` files, with the (minimum, maximum) number of values each one takes.
+/// @NOTE Optional values are always numbers e.g. `-s 2 2` is short for `-s 2 2 1`
+const MTL_TEXTURE_OPTIONS: [(&str, usize, usize); 13] = [
+    ("blendu", 1, 1),
+    ("blendv", 1, 1),
+    ("bm", 1, 1),
+    ("boost", 1, 1),
+    ("cc", 1, 1),
+    ("clamp", 1, 1),
+    ("imfchan", 1, 1),
+    ("mm", 2, 2),
+    ("o", 1, 3),
+    ("s", 1, 3),
+    ("t", 1, 3),
+    ("texres", 1, 1),
+    ("type", 1, 1),
+];
+/// Top-level arrays in a `.gltf` file whose items can refer to other files with a `uri`
+const GLTF_URI_ARRAYS: [&str; 2] = ["buffers", "images"];
+
+// Types
+/// A mesh file (or one of its supplementary files), with every reference to another file resolved
+pub struct MeshFile {
+    /// Contents of the file, with every reference rewritten to a normalised path relative to the file
+    pub contents: Vec<u8>,
+    /// Paths (relative to the project root) of every file referred to, in the order they are first referred to
+    pub dependencies: Vec<String>,
+    /// A diagnostic for each reference that could not be resolved
+    pub diagnostics: Vec<BuildDiagnostic>,
+}
+
+/// Resolve the files a mesh (or one of its supplementary files) refers to e.g. the `.mtl` files an `.obj` uses,
+/// the textures used by an `.mtl`, or the buffers and images used by a `.gltf`.
+/// `file_path` is the (project-relative) path of the file, and `asset_paths` are the (project-relative) paths of every
+/// file that can be referred to. References are often written by exporters as absolute paths, or with `\`, so each one is
+/// rewritten to a path relative to the file, using `/`.
+/// Files of any other type are returned unchanged, with no dependencies.
+pub fn process_mesh_file(file_path: &str, contents: Vec<u8>, asset_paths: &HashSet<String>) -> MeshFile {
+    let file_path = file_path.replace('\\', "/");
+    let directory = match file_path.rsplit_once('/') {
+        Some((directory, _)) => directory.to_string(),
+        None => String::new(),
+    };
+    let mut resolver = ReferenceResolver {
+        file_path: &file_path,
+        directory: &directory,
+        asset_paths,
+        dependencies: Vec::new(),
+        diagnostics: Vec::new(),
+    };
+
+    let contents = match Path::new(&file_path).extension().and_then(|extension| extension.to_str()) {
+        // @NOTE `mtllib` can list more than one file, separated by whitespace
+        Some("obj") => rewrite_lines(contents, |line| match split_statement(line) {
+            Some((b"mtllib", arguments)) => arguments.split(u8::is_ascii_whitespace)
+                .filter(|reference| !reference.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        }, &mut resolver),
+        Some("mtl") => rewrite_lines(contents, |line| match split_statement(line) {
+            Some((statement, arguments)) if statement.starts_with(b"map_") || MTL_TEXTURE_STATEMENTS.iter().any(|name| name.as_bytes() == statement) => {
+                vec![get_mtl_texture_file_name(arguments)]
+            },
+            _ => Vec::new(),
+        }, &mut resolver),
+        Some("gltf") => rewrite_gltf(contents, &mut resolver),
+        _ => contents,
+    };
+
+    MeshFile {
+        contents,
+        dependencies: resolver.dependencies,
+        diagnostics: resolver.diagnostics,
+    }
+}
+
+/// Resolves references within a single file, collecting its dependencies (and any problems) along the way
+struct ReferenceResolver<'a> {
+    file_path: &'a str,
+    /// Directory the file is in, relative to the project root
+    directory: &'a str,
+    asset_paths: &'a HashSet<String>,
+    dependencies: Vec<String>,
+    diagnostics: Vec<BuildDiagnostic>,
+}
+
+impl ReferenceResolver<'_> {
+    /// Resolve a reference to another file, returning the path to write in its place (relative to the file).
+    /// Returns `None` (and records a diagnostic) if the file cannot be found.
+    fn resolve(&mut self, reference: &str, location: Option<&SourceLocation>) -> Option<String> {
+        let Some(dependency_path) = self.find_asset(reference) else {
+            let message = format!("Cannot find file '{reference}'. It must be an asset in the project");
+            self.diagnostics.push(match location {
+                Some(location) => BuildDiagnostic::error_at(self.file_path, location, message),
+                None => BuildDiagnostic::error(self.file_path, message),
+            });
+            return None;
+        };
+
+        // @NOTE Written without a leading `./` e.g. `textures/a.png` rather than `./textures/a.png`
+        let relative_path = get_relative_specifier(&format!("./{}", self.file_path), &format!("./{dependency_path}"));
+        let relative_path = relative_path.strip_prefix("./").unwrap_or(&relative_path).to_string();
+        if !self.dependencies.contains(&dependency_path) {
+            self.dependencies.push(dependency_path);
+        }
+        Some(relative_path)
+    }
+
+    /// Find the (project-relative) path of the asset a reference refers to
+    fn find_asset(&self, reference: &str) -> Option<String> {
+        let reference = reference.replace('\\', "/");
+        let is_absolute = reference.starts_with('/')
+            || reference.as_bytes().get(1) == Some(&b':')
+            || reference.starts_with("file:");
+
+        if !is_absolute {
+            return normalize_path(self.directory, &reference).filter(|path| self.asset_paths.contains(path));
+        }
+
+        // @NOTE Exporters often write the path the file had on the artist's machine. Find the asset whose path
+        // shares the longest ending with it (e.g. `C:/Users/me/Game/textures/a.png` => `textures/a.png`),
+        // as long as only one asset does.
+        let segments: Vec<&str> = reference.split('/').filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..").collect();
+        for start in 0..segments.len() {
+            let suffix = segments[start..].join("/");
+            let mut matches = self.asset_paths.iter()
+                .filter(|path| **path == suffix || path.ends_with(&format!("/{suffix}")));
+            match (matches.next(), matches.next()) {
+                (Some(path), None) => return Some(path.clone()),
+                (Some(_), Some(_)) => return None,
+                _ => {},
+            }
+        }
+        None
+    }
+}
+
+/// Rewrite the file names referred to by each line of a text file (as found by `get_references`), keeping every other byte as-is
+fn rewrite_lines(
+    contents: Vec<u8>,
+    get_references: impl Fn(&[u8]) -> Vec<&[u8]>,
+    resolver: &mut ReferenceResolver,
+) -> Vec<u8> {
+    let mut output = Vec::with_capacity(contents.len());
+    for (line_index, line) in contents.split_inclusive(|byte| *byte == b'\n').enumerate() {
+        let ending_length = line.iter().rev().take_while(|byte| matches!(byte, b'\r' | b'\n')).count();
+        let line_without_ending = &line[..line.len() - ending_length];
+
+        // @NOTE Meshes are not always UTF-8 (e.g. paths written by exporters in Latin-1), so lines are never decoded
+        // to be written back. Only the bytes of each rewritten reference are replaced.
+        let mut copied_length = 0;
+        for reference in get_references(line_without_ending).into_iter().filter(|reference| !reference.is_empty()) {
+            // @NOTE Each reference is always a slice of the line, so its position can be found from its pointer
+            let start = reference.as_ptr() as usize - line.as_ptr() as usize;
+            let location = SourceLocation {
+                line: line_index + 1,
+                column: start + 1,
+                snippet: Some(String::from_utf8_lossy(line_without_ending).into_owned()),
+            };
+            let relative_path = resolver.resolve(&String::from_utf8_lossy(reference), Some(&location))
+                .filter(|relative_path| relative_path.as_bytes() != reference);
+            if let Some(relative_path) = relative_path {
+                output.extend_from_slice(&line[copied_length..start]);
+                output.extend_from_slice(relative_path.as_bytes());
+                copied_length = start + reference.len();
+            }
+        }
+        output.extend_from_slice(&line[copied_length..]);
+    }
+    output
+}
+
+/// Split a line of a text file into its statement and arguments e.g. `mtllib a.mtl` => (`mtllib`, `a.mtl`)
+fn split_statement(line: &[u8]) -> Option<(&[u8], &[u8])> {
+    let line = line.trim_ascii_start();
+    let length = line.iter().position(u8::is_ascii_whitespace)?;
+    Some((&line[..length], line[length..].trim_ascii()))
+}
+
+/// Rewrite the `uri` of every buffer and image in a `.gltf` file that refers to another file (rather than embedded data)
+fn rewrite_gltf(contents: Vec<u8>, resolver: &mut ReferenceResolver) -> Vec<u8> {
+    let mut gltf: Value = match serde_json::from_slice(&contents) {
+        Ok(gltf) => gltf,
+        Err(error) => {
+            let location = SourceLocation {
+                line: error.line(),
+                column: error.column(),
+                snippet: None,
+            };
+            resolver.diagnostics.push(BuildDiagnostic::error_at(resolver.file_path, &location, format!("Failed to parse glTF: {error}")));
+            return contents;
+        },
+    };
+
+    let mut is_modified = false;
+    for array_name in GLTF_URI_ARRAYS {
+        let Some(items) = gltf.get_mut(array_name).and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for item in items.iter_mut() {
+            let Some(uri) = item.get("uri").and_then(Value::as_str) else {
+                continue;
+            };
+            if uri.starts_with("data:") {
+                continue;
+            }
+
+            let reference = decode_uri(uri);
+            if let Some(relative_path) = resolver.resolve(&reference, None) {
+                let relative_uri = encode_uri(&relative_path);
+                if relative_uri != uri {
+                    item["uri"] = Value::String(relative_uri);
+                    is_modified = true;
+                }
+            }
+        }
+    }
+
+    match is_modified {
+        true => serde_json::to_vec(&gltf).unwrap_or(contents),
+        false => contents,
+    }
+}
+
+/// File name of a texture statement in an `.mtl` file, after any options (see [`MTL_TEXTURE_OPTIONS`])
+/// e.g. `-imfchan r -s 1 1 my texture.png` => `my texture.png`
+fn get_mtl_texture_file_name(arguments: &[u8]) -> &[u8] {
+    let mut remaining = arguments.trim_ascii();
+    while remaining.starts_with(b"-") {
+        let Some((option, rest)) = split_token(remaining) else {
+            break;
+        };
+        remaining = rest;
+
+        // @NOTE Options that are not known are assumed to only take numbers
+        let (min_value_count, max_value_count) = MTL_TEXTURE_OPTIONS.iter()
+            .find(|(name, _, _)| name.as_bytes() == &option[1..])
+            .map(|(_, min_value_count, max_value_count)| (*min_value_count, *max_value_count))
+            .unwrap_or((0, usize::MAX));
+        for value_index in 0..max_value_count {
+            let Some((value, rest)) = split_token(remaining) else {
+                break;
+            };
+            let is_number = std::str::from_utf8(value).is_ok_and(|value| value.parse::<f64>().is_ok());
+            if value_index >= min_value_count && !is_number {
+                break;
+            }
+            remaining = rest;
+        }
+    }
+    remaining.trim_ascii_end()
+}
+
+/// Split the first whitespace-separated token from the rest of `text`. `None` if there is only one token.
+fn split_token(text: &[u8]) -> Option<(&[u8], &[u8])> {
+    let length = text.iter().position(u8::is_ascii_whitespace)?;
+    Some((&text[..length], text[length..].trim_ascii_start()))
+}
+
+/// Decode percent-encoded characters in a URI e.g. `my%20texture.png` => `my texture.png`
+fn decode_uri(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let hex = bytes.get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[index], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                index += 3;
+            },
+            (byte, _) => {
+                decoded.push(byte);
+                index += 1;
+            },
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Percent-encode a relative path for use as a URI e.g. `my texture.png` => `my%20texture.png`
+fn encode_uri(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => (byte as char).to_string(),
+            byte => format!("%{byte:02X}"),
+        })
+        .collect()
+}
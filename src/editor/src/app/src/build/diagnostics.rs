@@ -0,0 +1,190 @@
+use serde::Serialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use swc_common::errors::{DiagnosticBuilder, Emitter, Level};
+use swc_common::source_map::SourceMap;
+use swc_common::BytePos;
+use swc_common::sync::Lrc;
+
+// Types
+/// How serious a build diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BuildDiagnosticSeverity {
+    /// The build cannot produce a cartridge
+    Error,
+    /// The build can continue, but something looks wrong
+    Warning,
+}
+
+/// A single problem found while building a cartridge, relating to one file
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildDiagnostic {
+    /// Path of the offending file, relative to the project root
+    pub path: PathBuf,
+    pub severity: BuildDiagnosticSeverity,
+    pub message: String,
+    /// 1-based line number, if the problem has a location in the file
+    pub line: Option<usize>,
+    /// 1-based column number, if the problem has a location in the file
+    pub column: Option<usize>,
+    /// The line of source code the problem was found on
+    pub snippet: Option<String>,
+}
+
+/// A location within a source file
+#[derive(Debug, Clone)]
+pub struct SourceLocation {
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column number
+    pub column: usize,
+    /// The line of source code at this location
+    pub snippet: Option<String>,
+}
+
+impl SourceLocation {
+    /// Resolve a position within a file in `source_map`
+    pub fn new(source_map: &SourceMap, position: BytePos) -> Self {
+        let loc = source_map.lookup_char_pos(position);
+        Self {
+            line: loc.line,
+            column: loc.col.0 + 1,
+            snippet: loc.file
+                .get_line(loc.line - 1)
+                .map(|line| line.into_owned()),
+        }
+    }
+}
+
+impl BuildDiagnostic {
+    /// Create an error diagnostic that has no location within the file (e.g. an IO error)
+    pub fn error(path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            severity: BuildDiagnosticSeverity::Error,
+            message: message.into(),
+            line: None,
+            column: None,
+            snippet: None,
+        }
+    }
+
+    /// Create an error diagnostic at a location within the file
+    pub fn error_at(path: impl Into<PathBuf>, location: &SourceLocation, message: impl Into<String>) -> Self {
+        Self {
+            line: Some(location.line),
+            column: Some(location.column),
+            snippet: location.snippet.clone(),
+            ..Self::error(path, message)
+        }
+    }
+
+    /// Create a warning diagnostic that has no location within the file
+    pub fn warning(path: impl Into<PathBuf>, message: impl Into<String>) -> Self {
+        Self {
+            severity: BuildDiagnosticSeverity::Warning,
+            ..Self::error(path, message)
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == BuildDiagnosticSeverity::Error
+    }
+}
+
+impl fmt::Display for BuildDiagnostic {
+    /// Format as `path:line:column: severity: message`, followed by the source snippet (if any)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            BuildDiagnosticSeverity::Error => "error",
+            BuildDiagnosticSeverity::Warning => "warning",
+        };
+
+        write!(f, "{}", self.path.display())?;
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, ":{line}:{column}")?;
+        }
+        write!(f, ": {severity}: {}", self.message)?;
+        if let Some(snippet) = &self.snippet {
+            write!(f, "\n    {snippet}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The reason a build did not produce a cartridge
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BuildError {
+    /// Problems were found while building. Contains every diagnostic that was collected
+    /// during the build (not just the first), so that all problems can be shown at once.
+    #[serde(rename_all = "camelCase")]
+    Failed { diagnostics: Vec<BuildDiagnostic> },
+    /// The build was cancelled before it finished
+    Cancelled,
+}
+
+impl BuildError {
+    /// Shorthand for a build failure caused by a single diagnostic
+    pub fn single(diagnostic: BuildDiagnostic) -> Self {
+        Self::Failed { diagnostics: vec![diagnostic] }
+    }
+}
+
+/// SWC diagnostic emitter that records diagnostics in memory (rather than writing them to stderr)
+/// so they can be returned to the caller of the build.
+pub struct DiagnosticCollector {
+    /// Path of the file being compiled, relative to the project root
+    path: PathBuf,
+    source_map: Lrc<SourceMap>,
+    diagnostics: Arc<Mutex<Vec<BuildDiagnostic>>>,
+}
+
+impl DiagnosticCollector {
+    pub fn new(
+        path: &Path,
+        source_map: Lrc<SourceMap>,
+        diagnostics: Arc<Mutex<Vec<BuildDiagnostic>>>,
+    ) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            source_map,
+            diagnostics,
+        }
+    }
+}
+
+impl Emitter for DiagnosticCollector {
+    fn emit(&mut self, db: &DiagnosticBuilder<'_>) {
+        let severity = match db.level {
+            Level::Bug | Level::Fatal | Level::PhaseFatal | Level::Error => BuildDiagnosticSeverity::Error,
+            Level::Warning => BuildDiagnosticSeverity::Warning,
+            // @NOTE Notes / help etc. are only ever attached to other diagnostics
+            _ => return,
+        };
+
+        let mut diagnostic = BuildDiagnostic {
+            path: self.path.clone(),
+            severity,
+            message: db.message(),
+            line: None,
+            column: None,
+            snippet: None,
+        };
+
+        // Resolve location of the problem within the source file
+        if let Some(span) = db.span.primary_span() {
+            if !span.is_dummy() {
+                let location = SourceLocation::new(&self.source_map, span.lo);
+                diagnostic.line = Some(location.line);
+                diagnostic.column = Some(location.column);
+                diagnostic.snippet = location.snippet;
+            }
+        }
+
+        self.diagnostics.lock().unwrap().push(diagnostic);
+    }
+}
@@ -0,0 +1,397 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use swc_common::{source_map::SourceMap, Span};
+use swc_ecma_ast::{
+    ExportAll, Ident, ImportDecl, ImportSpecifier, Module, NamedExport, Str, TsExprWithTypeArgs, TsImportEqualsDecl,
+    TsInterfaceDecl, TsModuleRef, TsType, TsTypeAliasDecl, TsTypeParamDecl,
+};
+use swc_ecma_parser::Syntax;
+use swc_ecma_visit::{Visit, VisitMut, VisitWith};
+
+use super::core_modules::CORE_MODULES;
+use super::diagnostics::{BuildDiagnostic, SourceLocation};
+use super::tsconfig::{PathAliases, TSCONFIG_FILE_NAME};
+use super::vendor::is_vendor_path;
+
+// Constants
+/// Scripts must be in this folder (relative to the project root) to be loaded by the runtime.
+/// @NOTE Must match `SCRIPT_PATH_PREFIX` in: src/runtime/src/ScriptLoader.ts
+pub const SCRIPT_PATH_PREFIX: &str = "scripts/";
+
+// Types
+/// A module imported by a script e.g. `import { World } from '@polyzone/core/modules/World'`
+#[derive(Debug, Clone)]
+pub struct ScriptImport {
+    /// The module specifier, as written in the script (or the relative specifier it is an alias of, once aliases have been rewritten)
+    pub specifier: String,
+    pub location: SourceLocation,
+}
+
+impl ScriptImport {
+    /// Create an error diagnostic located at this import
+    fn error(&self, script_path: &str, message: String) -> BuildDiagnostic {
+        BuildDiagnostic::error_at(script_path, &self.location, message)
+    }
+}
+
+/// Find every module imported by a script at runtime, given the syntax it was parsed with.
+/// Type-only imports are ignored, as they do not exist once compiled. So are TypeScript imports whose bindings are
+/// never used as values (e.g. `import { Player } from './player'` where `Player` is only used in type annotations),
+/// which are removed when the script is compiled, in the same way as TypeScript.
+pub fn find_imports(cm: &SourceMap, module: &Module, syntax: Syntax) -> Vec<ScriptImport> {
+    let value_references = syntax.typescript().then(|| {
+        let mut collector = ValueReferenceCollector { names: HashSet::new() };
+        module.visit_with(&mut collector);
+        collector.names
+    });
+    let mut collector = ImportCollector {
+        cm,
+        value_references,
+        imports: Vec::new(),
+    };
+    module.visit_with(&mut collector);
+    collector.imports
+}
+
+/// Relative specifier for each of a script's imports that is an alias (see [`PathAliases`]) of another script,
+/// keyed by the specifier as written e.g. `@game/player` => `../player`.
+/// `module_id` is the ID of the script's module, and `is_script` is whether a module ID belongs to a script in the build.
+pub fn resolve_aliased_imports(
+    imports: &[ScriptImport],
+    module_id: &str,
+    path_aliases: &PathAliases,
+    is_script: impl Fn(&str) -> bool,
+) -> BTreeMap<String, String> {
+    imports.iter()
+        .filter(|import| !is_relative_specifier(&import.specifier))
+        .filter_map(|import| {
+            let dependency_module_id = path_aliases.resolve(&import.specifier, &is_script)?;
+            Some((import.specifier.clone(), get_relative_specifier(module_id, &dependency_module_id)))
+        })
+        .collect()
+}
+
+/// Check that every import in every script can be resolved to another script or a core module,
+/// and that there are no import cycles (which the runtime's `ScriptLoader` cannot load).
+/// `scripts` are the (project-relative) paths of every script in the build, with their imports
+/// (after aliases have been rewritten, see [`resolve_aliased_imports`]).
+pub fn validate_imports(scripts: &[(&str, Vec<ScriptImport>)], path_aliases: &PathAliases) -> Vec<BuildDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    // Module IDs of every script, the same way `ScriptLoader` names them
+    let mut script_indices_by_module_id = HashMap::new();
+    for (index, (script_path, _)) in scripts.iter().enumerate() {
+        match get_module_id(script_path) {
+            Some(module_id) => {
+                script_indices_by_module_id.insert(module_id, index);
+            },
+            None => diagnostics.push(BuildDiagnostic::error(
+                *script_path,
+                format!("Scripts must be inside the `{SCRIPT_PATH_PREFIX}` folder to be loaded"),
+            )),
+        }
+    }
+
+    // Resolve every import, building a graph of which scripts depend on which
+    // @NOTE Each dependency is stored with the import it came from, so problems can be located
+    let mut dependencies: Vec<Vec<(usize, &ScriptImport)>> = vec![Vec::new(); scripts.len()];
+    for (index, (script_path, imports)) in scripts.iter().enumerate() {
+        let Some(module_id) = get_module_id(script_path) else {
+            continue;
+        };
+
+        for import in imports.iter() {
+            let specifier = import.specifier.as_str();
+
+            if !is_relative_specifier(specifier) {
+                // Bare specifiers are core modules e.g. `@polyzone/core/world`,
+                // or modules from npm packages (which imports of packages are rewritten to, see `vendor`)
+                if !CORE_MODULES.contains(&specifier) && !is_vendor_path(specifier) {
+                    let message = if specifier.starts_with(CORE_MODULES[0]) {
+                        format!("Unknown core module '{specifier}'")
+                    } else if path_aliases.matches(specifier) {
+                        format!("Cannot find script '{specifier}' (using `paths` in {TSCONFIG_FILE_NAME})")
+                    } else {
+                        format!("Cannot import '{specifier}'. Scripts can only import other scripts (using a relative path or an alias from {TSCONFIG_FILE_NAME}), core modules or npm packages")
+                    };
+                    diagnostics.push(import.error(script_path, message));
+                }
+                continue;
+            }
+
+            let Some(dependency_module_id) = resolve_relative_specifier(&module_id, specifier) else {
+                diagnostics.push(import.error(script_path, format!("Cannot import '{specifier}'. It is outside the `{SCRIPT_PATH_PREFIX}` folder")));
+                continue;
+            };
+
+            match script_indices_by_module_id.get(&dependency_module_id) {
+                Some(&dependency_index) => dependencies[index].push((dependency_index, import)),
+                None => {
+                    // @NOTE Module IDs have no file extension, so e.g. `./Player.ts` can never be loaded
+                    let message = match script_indices_by_module_id.contains_key(&strip_extension(&dependency_module_id)) {
+                        true => format!("Cannot import '{specifier}'. Imports of other scripts must not include a file extension"),
+                        false => format!("Cannot find script '{specifier}'"),
+                    };
+                    diagnostics.push(import.error(script_path, message));
+                },
+            }
+        }
+    }
+
+    // Find import cycles
+    let mut cycle_finder = CycleFinder {
+        scripts,
+        dependencies: &dependencies,
+        states: vec![VisitState::NotVisited; scripts.len()],
+        stack: Vec::new(),
+        diagnostics: &mut diagnostics,
+    };
+    for index in 0..scripts.len() {
+        cycle_finder.visit(index);
+    }
+
+    diagnostics
+}
+
+/// Convert a script's path to the ID of its module e.g. `scripts/player/Player.ts` => `./player/Player`.
+/// Returns `None` if the script is not in the scripts folder.
+/// @NOTE Must match `pathToModuleId()` in: src/runtime/src/ScriptLoader.ts
+pub fn get_module_id(script_path: &str) -> Option<String> {
+    let script_path = script_path.replace('\\', "/");
+    let relative_path = script_path.strip_prefix(SCRIPT_PATH_PREFIX)?;
+    Some(format!("./{}", strip_extension(relative_path)))
+}
+
+fn strip_extension(path: &str) -> String {
+    match Path::new(path).extension() {
+        Some(extension) => path[..path.len() - extension.len() - 1].to_string(),
+        None => path.to_string(),
+    }
+}
+
+pub fn is_relative_specifier(specifier: &str) -> bool {
+    specifier.starts_with("./") || specifier.starts_with("../")
+}
+
+/// Resolve a relative import against the module ID of the script it is in
+/// e.g. `../util/Math` in `./player/Player` => `./util/Math`.
+/// Returns `None` if the result would be outside the scripts folder.
+/// @NOTE Must match `resolveModuleId()` in: src/runtime/src/ScriptLoader.ts
+pub fn resolve_relative_specifier(module_id: &str, specifier: &str) -> Option<String> {
+    let mut segments: Vec<&str> = module_id.split('/').skip(1).collect();
+    // Relative to the directory the script is in
+    segments.pop();
+
+    for segment in specifier.split('/') {
+        match segment {
+            "." | "" => {},
+            ".." => {
+                segments.pop()?;
+            },
+            segment => segments.push(segment),
+        }
+    }
+
+    Some(format!("./{}", segments.join("/")))
+}
+
+/// Relative specifier that imports one module from another, i.e. the reverse of [`resolve_relative_specifier`]
+/// e.g. `./util/Math` from `./player/Player` => `../util/Math`
+pub fn get_relative_specifier(module_id: &str, dependency_module_id: &str) -> String {
+    let mut folder_segments: Vec<&str> = module_id.split('/').skip(1).collect();
+    folder_segments.pop();
+    let dependency_segments: Vec<&str> = dependency_module_id.split('/').skip(1).collect();
+
+    let common_count = folder_segments.iter()
+        .zip(dependency_segments.iter())
+        .take_while(|(segment, dependency_segment)| segment == dependency_segment)
+        .count();
+    let remaining_segments = dependency_segments[common_count..].join("/");
+    match folder_segments.len() - common_count {
+        0 => format!("./{remaining_segments}"),
+        parent_count => format!("{}{remaining_segments}", "../".repeat(parent_count)),
+    }
+}
+
+/// SWC visitor that rewrites the specifier of imports e.g. to replace aliases with relative specifiers
+/// (see [`resolve_aliased_imports`]). Visits the same imports as [`ImportCollector`].
+pub struct ImportRewriter<'a> {
+    specifiers: &'a BTreeMap<String, String>,
+}
+
+impl<'a> ImportRewriter<'a> {
+    /// `specifiers` maps each specifier (as written) to its replacement
+    pub fn new(specifiers: &'a BTreeMap<String, String>) -> Self {
+        Self { specifiers }
+    }
+
+    fn rewrite(&self, specifier: &mut Str) {
+        if let Some(replacement) = self.specifiers.get(specifier.value.as_str()) {
+            specifier.value = replacement.as_str().into();
+            // @NOTE Otherwise the specifier is written out as it was originally
+            specifier.raw = None;
+        }
+    }
+}
+
+impl VisitMut for ImportRewriter<'_> {
+    fn visit_mut_import_decl(&mut self, import: &mut ImportDecl) {
+        self.rewrite(&mut import.src);
+    }
+
+    fn visit_mut_named_export(&mut self, export: &mut NamedExport) {
+        if let Some(src) = &mut export.src {
+            self.rewrite(src);
+        }
+    }
+
+    fn visit_mut_export_all(&mut self, export: &mut ExportAll) {
+        self.rewrite(&mut export.src);
+    }
+
+    fn visit_mut_ts_import_equals_decl(&mut self, import: &mut TsImportEqualsDecl) {
+        if let TsModuleRef::TsExternalModuleRef(module_ref) = &mut import.module_ref {
+            self.rewrite(&mut module_ref.expr);
+        }
+    }
+}
+
+/// SWC visitor that collects all the imports in a module
+struct ImportCollector<'a> {
+    cm: &'a SourceMap,
+    /// Names referred to as values in the module (see [`ValueReferenceCollector`]).
+    /// `None` if unused imports are kept when the module is compiled i.e. it is not TypeScript.
+    value_references: Option<HashSet<String>>,
+    imports: Vec<ScriptImport>,
+}
+
+impl ImportCollector<'_> {
+    fn add_import(&mut self, specifier: &Str, span: Span) {
+        self.imports.push(ScriptImport {
+            specifier: specifier.value.to_string(),
+            location: SourceLocation::new(self.cm, span.lo),
+        });
+    }
+
+    /// Whether a binding declared by an import is kept once the module is compiled
+    fn is_used(&self, local: &Ident) -> bool {
+        self.value_references.as_ref().is_none_or(|names| names.contains(&*local.sym))
+    }
+}
+
+impl Visit for ImportCollector<'_> {
+    /// e.g. `import { Vector3 } from '@polyzone/core/util'`
+    /// @NOTE Imports without any bindings (e.g. `import './setup'`) are always kept
+    fn visit_import_decl(&mut self, import: &ImportDecl) {
+        let is_used = import.specifiers.is_empty() || import.specifiers.iter().any(|specifier| match specifier {
+            ImportSpecifier::Named(named) => !named.is_type_only && self.is_used(&named.local),
+            ImportSpecifier::Default(default) => self.is_used(&default.local),
+            ImportSpecifier::Namespace(namespace) => self.is_used(&namespace.local),
+        });
+        if !import.type_only && is_used {
+            self.add_import(&import.src, import.span);
+        }
+    }
+
+    /// e.g. `export { Vector3 } from '@polyzone/core/util'`
+    fn visit_named_export(&mut self, export: &NamedExport) {
+        if let Some(src) = &export.src {
+            if !export.type_only {
+                self.add_import(src, export.span);
+            }
+        }
+    }
+
+    /// e.g. `export * from './util'`
+    fn visit_export_all(&mut self, export: &ExportAll) {
+        if !export.type_only {
+            self.add_import(&export.src, export.span);
+        }
+    }
+
+    /// e.g. `import Util = require('./util')`
+    fn visit_ts_import_equals_decl(&mut self, import: &TsImportEqualsDecl) {
+        if let TsModuleRef::TsExternalModuleRef(module_ref) = &import.module_ref {
+            if !import.is_type_only && self.is_used(&import.id) {
+                self.add_import(&module_ref.expr, import.span);
+            }
+        }
+    }
+}
+
+/// SWC visitor that collects the name of every identifier referred to as a value i.e. outside of types.
+/// @NOTE Names are not scoped, so a local variable with the same name as an import counts as a use of the import.
+/// At worst, this keeps an import that TypeScript would remove.
+struct ValueReferenceCollector {
+    names: HashSet<String>,
+}
+
+impl Visit for ValueReferenceCollector {
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.names.insert(ident.sym.to_string());
+    }
+
+    /// The bindings an import declares are not uses of it
+    fn visit_import_decl(&mut self, _import: &ImportDecl) {}
+
+    fn visit_ts_type(&mut self, _type: &TsType) {}
+
+    fn visit_ts_type_param_decl(&mut self, _type_params: &TsTypeParamDecl) {}
+
+    /// e.g. `implements Updatable`
+    fn visit_ts_expr_with_type_args(&mut self, _expr: &TsExprWithTypeArgs) {}
+
+    fn visit_ts_interface_decl(&mut self, _interface: &TsInterfaceDecl) {}
+
+    fn visit_ts_type_alias_decl(&mut self, _type_alias: &TsTypeAliasDecl) {}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum VisitState {
+    NotVisited,
+    /// Currently on the stack i.e. reaching this script again means there is a cycle
+    Visiting,
+    Visited,
+}
+
+/// Depth-first search of the script import graph, reporting each cycle found as a diagnostic
+struct CycleFinder<'a> {
+    scripts: &'a [(&'a str, Vec<ScriptImport>)],
+    dependencies: &'a [Vec<(usize, &'a ScriptImport)>],
+    states: Vec<VisitState>,
+    /// Scripts currently being visited, in order
+    stack: Vec<usize>,
+    diagnostics: &'a mut Vec<BuildDiagnostic>,
+}
+
+impl CycleFinder<'_> {
+    fn visit(&mut self, index: usize) {
+        if self.states[index] != VisitState::NotVisited {
+            return;
+        }
+
+        self.states[index] = VisitState::Visiting;
+        self.stack.push(index);
+
+        for &(dependency_index, import) in self.dependencies[index].iter() {
+            match self.states[dependency_index] {
+                VisitState::NotVisited => self.visit(dependency_index),
+                VisitState::Visiting => {
+                    // Found a cycle. Report it at the import that completes it.
+                    let cycle_start = self.stack.iter().position(|&stack_index| stack_index == dependency_index).unwrap();
+                    let cycle = self.stack[cycle_start..].iter()
+                        .chain(std::iter::once(&dependency_index))
+                        .map(|&stack_index| self.scripts[stack_index].0)
+                        .collect::<Vec<_>>()
+                        .join(" -> ");
+                    let script_path = self.scripts[index].0;
+                    self.diagnostics.push(import.error(script_path, format!("Import cycle detected: {cycle}")));
+                },
+                VisitState::Visited => {},
+            }
+        }
+
+        self.stack.pop();
+        self.states[index] = VisitState::Visited;
+    }
+}
@@ -0,0 +1,211 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::cartridge::header::{parse_engine_version, CartridgeHeader, CARTRIDGE_FORMAT_VERSION, ENGINE_VERSION};
+use crate::cartridge::manifest::{AssetDefinition, CartridgeArchiveManifest, NamedScene};
+use crate::filesystem::assets::AssetType;
+use crate::filesystem::project::{ProjectDefinition, ProjectManifest};
+use crate::filesystem::scenes::read_scene_definition_from_path;
+use super::diagnostics::{BuildDiagnostic, BuildError};
+use super::BuildOptions;
+
+// Constants
+/// Environment variable that fixes the build timestamp, if it is not given in [`BuildOptions::build_timestamp`]
+pub const SOURCE_DATE_EPOCH_VARIABLE: &str = "SOURCE_DATE_EPOCH";
+
+/// Assemble the cartridge header from the project's manifest.
+/// The build timestamp is the current time, unless it is fixed by `options` or [`SOURCE_DATE_EPOCH_VARIABLE`].
+/// `project_file_name` is only used to report problems with the project's manifest.
+pub fn create_cartridge_header(
+    project_manifest: &ProjectManifest,
+    project_file_name: &Path,
+    options: &BuildOptions,
+) -> Result<CartridgeHeader, BuildError> {
+    let min_engine_version = match &project_manifest.min_engine_version {
+        Some(min_engine_version) => {
+            let Some(version) = parse_engine_version(min_engine_version) else {
+                return Err(BuildError::single(BuildDiagnostic::error(
+                    project_file_name,
+                    format!("Invalid minimum engine version '{min_engine_version}'. Expected a version like '{ENGINE_VERSION}'"),
+                )));
+            };
+            // @NOTE The engine the editor builds for is the newest one it knows about
+            if version > parse_engine_version(ENGINE_VERSION).unwrap() {
+                return Err(BuildError::single(BuildDiagnostic::error(
+                    project_file_name,
+                    format!("Minimum engine version '{min_engine_version}' is newer than the engine this editor builds for ({ENGINE_VERSION})"),
+                )));
+            }
+            min_engine_version.trim().to_string()
+        },
+        None => ENGINE_VERSION.to_string(),
+    };
+
+    let build_timestamp = match options.build_timestamp {
+        Some(build_timestamp) => build_timestamp,
+        // @NOTE `SOURCE_DATE_EPOCH` is the standard way of fixing timestamps for reproducible builds: https://reproducible-builds.org/specs/source-date-epoch/
+        None => match std::env::var(SOURCE_DATE_EPOCH_VARIABLE) {
+            Ok(source_date_epoch) => source_date_epoch.trim().parse::<u64>().map_err(|_| {
+                BuildError::single(BuildDiagnostic::error(
+                    "",
+                    format!("Invalid {SOURCE_DATE_EPOCH_VARIABLE} '{source_date_epoch}'. Expected a number of seconds since the Unix epoch"),
+                ))
+            })?,
+            Err(_) => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|duration| duration.as_secs())
+                .unwrap_or_default(),
+        },
+    };
+
+    Ok(CartridgeHeader {
+        format_version: CARTRIDGE_FORMAT_VERSION,
+        title: project_manifest.project_name.clone(),
+        author: project_manifest.author.clone(),
+        description: project_manifest.description.clone(),
+        game_version: project_manifest.version.clone(),
+        min_engine_version,
+        build_timestamp,
+        build_profile: options.profile,
+        build_variant: options.variant.clone(),
+    })
+}
+
+/// Assemble the cartridge manifest for a project, reading every scene from disk.
+/// Scenes are ordered by the project's scene list (see [`ProjectDefinition::scene_list`]), with the entry scene first.
+/// The entry scene is `entry_scene_id` if given (e.g. to playtest a particular scene), otherwise the project's entry scene.
+/// If `included_scene_ids` is given (i.e. when building a variant), only those scenes are included.
+/// `project_file_name` is only used to report problems with the project's scene list and entry scene.
+pub async fn create_cartridge_manifest(
+    project_root: &Path,
+    project_definition: &ProjectDefinition,
+    project_file_name: &Path,
+    entry_scene_id: Option<Uuid>,
+    included_scene_ids: Option<&HashSet<Uuid>>,
+) -> Result<CartridgeArchiveManifest, BuildError> {
+    let mut diagnostics = Vec::<BuildDiagnostic>::new();
+
+    // Check that the scene list and entry scene only refer to scenes in the project
+    let scene_paths_by_id: HashMap<Uuid, &Path> = project_definition.scenes.iter()
+        .map(|scene| (scene.id, scene.path.as_path()))
+        .collect();
+    let mut scene_list = Vec::new();
+    let mut scene_names = HashSet::new();
+    for named_scene in project_definition.scene_list.iter() {
+        if named_scene.name.trim().is_empty() {
+            diagnostics.push(BuildDiagnostic::error(project_file_name, format!("Scene '{}' in `sceneList` has no name", named_scene.id)));
+        } else if !scene_names.insert(named_scene.name.as_str()) {
+            diagnostics.push(BuildDiagnostic::error(project_file_name, format!("Scene name '{}' is used more than once in `sceneList`", named_scene.name)));
+        }
+        match scene_paths_by_id.get(&named_scene.id) {
+            Some(path) => scene_list.push(NamedScene {
+                name: named_scene.name.clone(),
+                path: path.to_path_buf(),
+            }),
+            None => diagnostics.push(BuildDiagnostic::error(
+                project_file_name,
+                format!("Scene '{}' in `sceneList` refers to scene with ID '{}' - it isn't one of the project's scenes", named_scene.name, named_scene.id),
+            )),
+        }
+    }
+    if let Some(project_entry_scene_id) = project_definition.entry_scene.filter(|scene_id| !scene_paths_by_id.contains_key(scene_id)) {
+        diagnostics.push(BuildDiagnostic::error(
+            project_file_name,
+            format!("`entryScene` refers to scene with ID '{project_entry_scene_id}' - it isn't one of the project's scenes"),
+        ));
+    }
+    if !diagnostics.is_empty() {
+        return Err(BuildError::Failed { diagnostics });
+    }
+
+    let is_included = |scene_id: &Uuid| included_scene_ids.is_none_or(|included_scene_ids| included_scene_ids.contains(scene_id));
+    let excluded_entry_scene_path = entry_scene_id.or(project_definition.entry_scene)
+        .filter(|scene_id| !is_included(scene_id))
+        .and_then(|scene_id| scene_paths_by_id.get(&scene_id));
+    if let Some(entry_scene_path) = excluded_entry_scene_path {
+        return Err(BuildError::single(BuildDiagnostic::error(
+            project_file_name,
+            format!("Entry scene '{}' is not one of the scenes included in this build", entry_scene_path.display()),
+        )));
+    }
+    // @NOTE Names of scenes that are not included are left out, rather than referring to scenes that are not in the cartridge
+    scene_list.retain(|named_scene| project_definition.scene_list.iter()
+        .any(|project_named_scene| project_named_scene.name == named_scene.name && is_included(&project_named_scene.id)));
+
+    // Listed scenes come first (in the order they are listed), followed by any other scenes
+    let mut scene_manifests = project_definition.scenes.iter()
+        .filter(|scene| is_included(&scene.id))
+        .collect::<Vec<_>>();
+    scene_manifests.sort_by_key(|scene| {
+        project_definition.scene_list.iter()
+            .position(|named_scene| named_scene.id == scene.id)
+            .unwrap_or(usize::MAX)
+    });
+
+    // Move the entry scene to be the first scene in the list
+    let entry_scene_id = entry_scene_id.or(project_definition.entry_scene);
+    if let Some(entry_scene_id) = entry_scene_id {
+        match scene_manifests.iter().position(|scene| scene.id == entry_scene_id) {
+            Some(entry_scene_index) => {
+                let entry_scene = scene_manifests.remove(entry_scene_index);
+                scene_manifests.insert(0, entry_scene);
+            }
+            None => {
+                return Err(BuildError::single(BuildDiagnostic::error(
+                    "",
+                    format!("Cannot set entry point to scene with ID '{entry_scene_id}' - it isn't one of the project's scenes"),
+                )));
+            }
+        }
+    }
+
+    let entry_scene = scene_manifests.first().map(|scene| scene.path.clone());
+
+    // Read scene definitions from disk
+    let mut scenes = Vec::new();
+    for scene_manifest in scene_manifests {
+        match read_scene_definition_from_path(&project_root.join(&scene_manifest.path)).await {
+            Ok(mut scene) => {
+                scene.path = scene_manifest.path.clone();
+                scenes.push(scene);
+            }
+            Err(error) => diagnostics.push(BuildDiagnostic::error(&scene_manifest.path, error)),
+        }
+    }
+
+    if !diagnostics.is_empty() {
+        return Err(BuildError::Failed { diagnostics });
+    }
+
+    let assets = project_definition.assets.iter()
+        .map(|asset| {
+            let asset_type = AssetType::from_path(&asset.path);
+            AssetDefinition {
+                id: asset.id,
+                asset_type,
+                path: match asset_type {
+                    // @NOTE Scripts are compiled, so need to be renamed to .js
+                    AssetType::Script => asset.path.with_extension("js"),
+                    _ => asset.path.clone(),
+                },
+                // @NOTE Filled in once the script has been compiled
+                script_component: None,
+                // @NOTE Filled in once meshes have been processed
+                dependencies: Vec::new(),
+            }
+        })
+        .collect();
+
+    Ok(CartridgeArchiveManifest {
+        scenes,
+        assets,
+        entry_scene,
+        scene_list,
+        // @NOTE Filled in once every file has been processed
+        vendor_modules: Vec::new(),
+        entries: Vec::new(),
+        aliases: Vec::new(),
+    })
+}
@@ -0,0 +1,279 @@
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use swc_common::{source_map::SourceMap, sync::Lrc, FileName, Spanned};
+use swc_ecma_ast::{
+    Accessibility, Class, ClassMember, ClassProp, Expr, Lit, PropName, TsEntityName, TsKeywordTypeKind,
+    TsLit, TsType, TsTypeRef, TsUnionOrIntersectionType, UnaryOp,
+};
+
+use super::components::find_classes;
+use super::core_modules::CORE_MODULES;
+use super::diagnostics::{BuildDiagnostic, SourceLocation};
+use super::compiler::CompilerConfig;
+use crate::filesystem::assets::AssetType;
+
+// Types
+/// The fields of a script component that can be edited in the inspector
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptPropertySchema {
+    /// Public fields declared by the script's default-exported class, in the order they are declared
+    pub properties: Vec<ScriptProperty>,
+}
+
+/// A single editable field of a script component e.g. `public speed: number = 5;`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptProperty {
+    pub name: String,
+    #[serde(flatten)]
+    pub property_type: ScriptPropertyType,
+    /// The value the field is initialised to, if it is a constant e.g. `5`, `new Vector3(0, 1, 0)`
+    pub default_value: Option<Value>,
+}
+
+/// Type of a script component field. Only types that the inspector can edit are supported.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ScriptPropertyType {
+    Number,
+    String,
+    Boolean,
+    Vector3,
+    Color3,
+    /// An `AssetReference` e.g. `AssetReference<'mesh'>`
+    #[serde(rename_all = "camelCase")]
+    Asset {
+        /// Type of asset that can be referenced. `None` if any type of asset can be referenced.
+        asset_type: Option<AssetType>,
+    },
+}
+
+/// Read a script from disk and find the fields of its default-exported class that can be edited in the inspector.
+/// `script_path` is relative to `project_root`.
+/// The script is parsed with the same syntax it is compiled with (see `compiler`).
+/// Returns an error if the script cannot be read or parsed.
+pub fn read_script_property_schema(project_root: &Path, script_path: &Path, compiler: &CompilerConfig) -> Result<ScriptPropertySchema, BuildDiagnostic> {
+    let path = project_root.join(script_path);
+    let file_contents = std::fs::read_to_string(&path)
+        .map_err(|error| BuildDiagnostic::error(script_path, format!("Failed to read file: {error}")))?;
+
+    let cm = Lrc::new(SourceMap::new(swc_common::FilePathMapping::empty()));
+    let source = cm.new_source_file(FileName::Real(path).into(), file_contents);
+    let mut recovered_errors = Vec::new();
+    let module = swc_ecma_parser::parse_file_as_module(
+        &source,
+        compiler.script_syntax(script_path),
+        compiler.target,
+        None,
+        &mut recovered_errors,
+    )
+        // @NOTE The parser recovers from some errors, but the script would still fail to compile
+        .and_then(|module| match recovered_errors.into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(module),
+        })
+        .map_err(|error| {
+            let location = SourceLocation::new(&cm, error.span().lo);
+            BuildDiagnostic::error_at(script_path, &location, error.kind().msg())
+        })?;
+
+    Ok(find_classes(&cm, &module).get_property_schema())
+}
+
+/// Find the fields of a class that can be edited in the inspector.
+/// `imports` are the script's imported bindings (see `components::get_import_bindings`).
+/// Only fields declared by the class itself are included (not those of any class it extends).
+/// Fields of unsupported types are ignored.
+pub fn get_class_properties(class: &Class, imports: &HashMap<String, (String, String)>) -> Vec<ScriptProperty> {
+    get_public_fields(class)
+        .filter_map(|(name, field)| {
+            let property_type = match &field.type_ann {
+                Some(type_ann) => get_property_type(&type_ann.type_ann, imports),
+                // @NOTE Fields without a type annotation are inferred from their initial value e.g. `speed = 5`
+                None => field.value.as_deref().and_then(|value| infer_property_type(value, imports)),
+            };
+            let Some(property_type) = property_type else {
+                log::debug!("[properties] Ignoring field '{}' with unsupported type", name);
+                return None;
+            };
+            let default_value = field.value.as_deref().and_then(|value| get_default_value(value, &property_type, imports));
+            Some(ScriptProperty {
+                name,
+                property_type,
+                default_value,
+            })
+        })
+        .collect()
+}
+
+/// Public, non-static fields declared by a class, with their names
+fn get_public_fields(class: &Class) -> impl Iterator<Item = (String, &ClassProp)> {
+    class.body.iter().filter_map(|member| match member {
+        ClassMember::ClassProp(field) if !field.is_static && !field.is_abstract => {
+            if matches!(field.accessibility, Some(Accessibility::Private | Accessibility::Protected)) {
+                return None;
+            }
+            match &field.key {
+                PropName::Ident(ident) => Some((ident.sym.to_string(), field)),
+                PropName::Str(name) => Some((name.value.to_string(), field)),
+                _ => None,
+            }
+        },
+        // @NOTE ES private fields (e.g. `#speed`) are `ClassMember::PrivateProp`, so are never included
+        _ => None,
+    })
+}
+
+/// Resolve a field's type annotation to a supported property type
+fn get_property_type(type_ann: &TsType, imports: &HashMap<String, (String, String)>) -> Option<ScriptPropertyType> {
+    match type_ann {
+        TsType::TsKeywordType(keyword) => match keyword.kind {
+            TsKeywordTypeKind::TsNumberKeyword => Some(ScriptPropertyType::Number),
+            TsKeywordTypeKind::TsStringKeyword => Some(ScriptPropertyType::String),
+            TsKeywordTypeKind::TsBooleanKeyword => Some(ScriptPropertyType::Boolean),
+            _ => None,
+        },
+        TsType::TsTypeRef(type_ref) => match get_core_export_name(&type_ref.type_name, imports)?.as_str() {
+            "Vector3" => Some(ScriptPropertyType::Vector3),
+            "Color3" => Some(ScriptPropertyType::Color3),
+            "AssetReference" => Some(ScriptPropertyType::Asset {
+                asset_type: get_asset_reference_type(type_ref),
+            }),
+            _ => None,
+        },
+        // e.g. `AssetReference<'mesh'> | undefined`
+        TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) => {
+            let mut types = union.types.iter().filter(|union_type| !matches!(
+                union_type.as_ref(),
+                TsType::TsKeywordType(keyword) if matches!(keyword.kind, TsKeywordTypeKind::TsUndefinedKeyword | TsKeywordTypeKind::TsNullKeyword),
+            ));
+            match (types.next(), types.next()) {
+                (Some(union_type), None) => get_property_type(union_type, imports),
+                _ => None,
+            }
+        },
+        TsType::TsParenthesizedType(parenthesized) => get_property_type(&parenthesized.type_ann, imports),
+        _ => None,
+    }
+}
+
+/// The type of asset an `AssetReference` refers to e.g. `AssetReference<'mesh'>` => `Mesh`
+/// @NOTE Must match `AssetReferenceType` in: src/core/src/util/AssetReference.ts
+fn get_asset_reference_type(type_ref: &TsTypeRef) -> Option<AssetType> {
+    let type_param = type_ref.type_params.as_ref()?.params.first()?;
+    let TsType::TsLitType(lit_type) = type_param.as_ref() else {
+        return None;
+    };
+    let TsLit::Str(asset_type) = &lit_type.lit else {
+        return None;
+    };
+    match asset_type.value.as_str() {
+        "mesh" => Some(AssetType::Mesh),
+        "texture" => Some(AssetType::Texture),
+        "sound" => Some(AssetType::Sound),
+        "script" => Some(AssetType::Script),
+        _ => None,
+    }
+}
+
+/// Infer the type of a field that has no type annotation from its initial value
+fn infer_property_type(value: &Expr, imports: &HashMap<String, (String, String)>) -> Option<ScriptPropertyType> {
+    match value {
+        Expr::Lit(Lit::Num(_)) => Some(ScriptPropertyType::Number),
+        Expr::Unary(unary) if unary.op == UnaryOp::Minus && matches!(unary.arg.as_ref(), Expr::Lit(Lit::Num(_))) => Some(ScriptPropertyType::Number),
+        Expr::Lit(Lit::Str(_)) => Some(ScriptPropertyType::String),
+        Expr::Lit(Lit::Bool(_)) => Some(ScriptPropertyType::Boolean),
+        Expr::New(new_expr) => match get_core_export_name_of_expr(&new_expr.callee, imports)?.as_str() {
+            "Vector3" => Some(ScriptPropertyType::Vector3),
+            "Color3" => Some(ScriptPropertyType::Color3),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Evaluate a field's initial value, if it is a constant of the field's type
+fn get_default_value(value: &Expr, property_type: &ScriptPropertyType, imports: &HashMap<String, (String, String)>) -> Option<Value> {
+    match property_type {
+        ScriptPropertyType::Number => get_number(value).map(Value::from),
+        ScriptPropertyType::String => match value {
+            Expr::Lit(Lit::Str(string)) => Some(Value::from(string.value.as_str())),
+            _ => None,
+        },
+        ScriptPropertyType::Boolean => match value {
+            Expr::Lit(Lit::Bool(boolean)) => Some(Value::from(boolean.value)),
+            _ => None,
+        },
+        // e.g. `new Vector3(0, 1, 0)`
+        ScriptPropertyType::Vector3 | ScriptPropertyType::Color3 => {
+            let Expr::New(new_expr) = value else {
+                return None;
+            };
+            let class_name = get_core_export_name_of_expr(&new_expr.callee, imports)?;
+            let args = new_expr.args.as_ref()?.iter()
+                .map(|arg| match arg.spread {
+                    Some(_) => None,
+                    None => get_number(&arg.expr),
+                })
+                .collect::<Option<Vec<_>>>()?;
+            match (class_name.as_str(), property_type, args.as_slice()) {
+                ("Vector3", ScriptPropertyType::Vector3, &[x, y, z]) => Some(json!({ "x": x, "y": y, "z": z })),
+                ("Color3", ScriptPropertyType::Color3, &[r, g, b]) => Some(json!({ "r": r, "g": g, "b": b })),
+                _ => None,
+            }
+        },
+        // @NOTE Asset references cannot be created in scripts, only chosen in the inspector
+        ScriptPropertyType::Asset { .. } => None,
+    }
+}
+
+/// Evaluate a numeric literal e.g. `5`, `-0.5`
+fn get_number(value: &Expr) -> Option<f64> {
+    match value {
+        Expr::Lit(Lit::Num(number)) => Some(number.value),
+        Expr::Unary(unary) if unary.op == UnaryOp::Minus => get_number(&unary.arg).map(|number| -number),
+        Expr::Paren(paren) => get_number(&paren.expr),
+        _ => None,
+    }
+}
+
+/// Name of the export from a core module that a type refers to e.g. `Vector3` or `Util.Vector3` => `Vector3`.
+/// Returns `None` if the type is not imported from a core module.
+fn get_core_export_name(type_name: &TsEntityName, imports: &HashMap<String, (String, String)>) -> Option<String> {
+    match type_name {
+        TsEntityName::Ident(ident) => get_core_import(ident.sym.as_str(), imports).map(|(_, export_name)| export_name.clone()),
+        TsEntityName::TsQualifiedName(qualified_name) => {
+            let TsEntityName::Ident(namespace) = &qualified_name.left else {
+                return None;
+            };
+            match get_core_import(namespace.sym.as_str(), imports)? {
+                (_, export_name) if export_name == "*" => Some(qualified_name.right.sym.to_string()),
+                _ => None,
+            }
+        },
+    }
+}
+
+/// Same as [`get_core_export_name`], but for a value e.g. the class in `new Vector3(...)`
+fn get_core_export_name_of_expr(expr: &Expr, imports: &HashMap<String, (String, String)>) -> Option<String> {
+    match expr {
+        Expr::Ident(ident) => get_core_import(ident.sym.as_str(), imports).map(|(_, export_name)| export_name.clone()),
+        Expr::Member(member) => {
+            let (Expr::Ident(namespace), Some(property)) = (member.obj.as_ref(), member.prop.as_ident()) else {
+                return None;
+            };
+            match get_core_import(namespace.sym.as_str(), imports)? {
+                (_, export_name) if export_name == "*" => Some(property.sym.to_string()),
+                _ => None,
+            }
+        },
+        _ => None,
+    }
+}
+
+fn get_core_import<'a>(local_name: &str, imports: &'a HashMap<String, (String, String)>) -> Option<&'a (String, String)> {
+    imports.get(local_name).filter(|(specifier, _)| CORE_MODULES.contains(&specifier.as_str()))
+}
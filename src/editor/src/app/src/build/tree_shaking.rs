@@ -0,0 +1,211 @@
+use globset::{GlobBuilder, GlobMatcher};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::rc::Rc;
+use swc_common::{source_map::SourceMap, sync::Lrc, FileName};
+use uuid::Uuid;
+
+use super::compiler::CompilerConfig;
+use super::dependencies::process_mesh_file;
+use super::diagnostics::{BuildDiagnostic, BuildError};
+use super::imports::{find_imports, get_module_id, is_relative_specifier, resolve_relative_specifier};
+use super::BuildOptions;
+use crate::cartridge::manifest::{ComponentDefinition, GameObjectDefinition, SceneDefinition};
+use crate::filesystem::assets::{AssetDefinition, AssetType};
+
+/// Find every asset that the game can use: those referenced by a component in any scene, along with everything
+/// they depend on (scripts they import, materials and textures used by meshes), and anything listed in
+/// `include_assets` (see [`AssetPattern`]) for assets that are only loaded dynamically.
+/// Assets matching `exclude_assets` are always left out; it is an error for anything else that is used to depend on one.
+/// Returns the IDs of those assets.
+/// Scripts are parsed with the same syntax they are compiled with, and their imports resolved with the same aliases
+/// (see `options`).
+/// `project_file_name` is used to report problems with `include_assets` and `exclude_assets`.
+pub fn find_used_assets(
+    project_root: &Path,
+    assets: &[AssetDefinition],
+    scenes: &[SceneDefinition],
+    include_assets: &[String],
+    exclude_assets: &[String],
+    options: &BuildOptions,
+    project_file_name: &Path,
+) -> Result<HashSet<Uuid>, BuildError> {
+    let mut diagnostics = Vec::new();
+    let include_patterns = parse_asset_patterns(include_assets, "includeAssets", project_file_name, &mut diagnostics);
+    let exclude_patterns = parse_asset_patterns(exclude_assets, "excludeAssets", project_file_name, &mut diagnostics);
+    if !diagnostics.is_empty() {
+        return Err(BuildError::Failed { diagnostics });
+    }
+
+    let assets_by_path: HashMap<String, &AssetDefinition> = assets.iter()
+        .map(|asset| (get_normalized_path(&asset.path), asset))
+        .collect();
+    let asset_paths: HashSet<String> = assets_by_path.keys().cloned().collect();
+    let script_ids_by_module_id: HashMap<String, Uuid> = assets.iter()
+        .filter(|asset| AssetType::from_path(&asset.path) == AssetType::Script)
+        .filter_map(|asset| Some((get_module_id(&get_normalized_path(&asset.path))?, asset.id)))
+        .collect();
+
+    // @NOTE Each pending asset is paired with a description of what uses it (if anything), for reporting used assets that are excluded
+    let mut pending_assets: Vec<(Uuid, Option<Rc<str>>)> = Vec::new();
+
+    // Assets referenced by game objects
+    for scene in scenes.iter() {
+        let used_by: Rc<str> = format!("Scene '{}'", scene.path.display()).into();
+        let mut objects: Vec<&GameObjectDefinition> = scene.objects.iter().collect();
+        while let Some(object) = objects.pop() {
+            objects.extend(object.children());
+            for component in object.components.iter() {
+                match component {
+                    ComponentDefinition::Mesh { mesh_file_id: Some(asset_id), .. } => pending_assets.push((*asset_id, Some(used_by.clone()))),
+                    ComponentDefinition::Script { script_file_id: Some(asset_id), .. } => pending_assets.push((*asset_id, Some(used_by.clone()))),
+                    _ => {},
+                }
+            }
+        }
+    }
+
+    // Assets included explicitly in the project file
+    // @NOTE These can overlap with excluded assets e.g. including a folder, except for one of its sub-folders
+    for include_pattern in include_patterns.iter() {
+        let included_asset_ids: Vec<Uuid> = assets.iter()
+            .filter(|asset| include_pattern.matches(asset))
+            .map(|asset| asset.id)
+            .collect();
+        if included_asset_ids.is_empty() {
+            diagnostics.push(BuildDiagnostic::error(project_file_name, format!("'{}' in `includeAssets` does not match any asset", include_pattern.entry)));
+        }
+        pending_assets.extend(included_asset_ids.into_iter().map(|asset_id| (asset_id, None)));
+    }
+    if !diagnostics.is_empty() {
+        return Err(BuildError::Failed { diagnostics });
+    }
+
+    // Follow dependencies of every used asset
+    // @NOTE Files that cannot be read or parsed are reported when they are built, so they are assumed to have no dependencies here
+    let assets_by_id: HashMap<Uuid, &AssetDefinition> = assets.iter().map(|asset| (asset.id, asset)).collect();
+    let mut used_asset_ids = HashSet::new();
+    while let Some((asset_id, used_by)) = pending_assets.pop() {
+        // @NOTE Components referring to assets that do not exist are reported elsewhere
+        let Some(asset) = assets_by_id.get(&asset_id) else {
+            continue;
+        };
+        let path = get_normalized_path(&asset.path);
+        if exclude_patterns.iter().any(|exclude_pattern| exclude_pattern.matches(asset)) {
+            if let Some(used_by) = used_by {
+                let diagnostic = BuildDiagnostic::error(project_file_name, format!("{used_by} uses '{path}', which is excluded by `excludeAssets`"));
+                if !diagnostics.iter().any(|existing: &BuildDiagnostic| existing.message == diagnostic.message) {
+                    diagnostics.push(diagnostic);
+                }
+            }
+            continue;
+        }
+        if !used_asset_ids.insert(asset_id) {
+            continue;
+        }
+        let used_by: Option<Rc<str>> = Some(format!("'{path}'").into());
+
+        match AssetType::from_path(&asset.path) {
+            AssetType::Script => {
+                let Some(module_id) = get_module_id(&path) else {
+                    continue;
+                };
+                let dependency_ids = find_script_imports(project_root, &asset.path, &options.compiler).into_iter()
+                    .filter_map(|specifier| match is_relative_specifier(&specifier) {
+                        true => resolve_relative_specifier(&module_id, &specifier),
+                        false => options.path_aliases.resolve(&specifier, |module_id| script_ids_by_module_id.contains_key(module_id)),
+                    })
+                    .filter_map(|dependency_module_id| script_ids_by_module_id.get(&dependency_module_id))
+                    .map(|dependency_id| (*dependency_id, used_by.clone()));
+                pending_assets.extend(dependency_ids);
+            },
+            AssetType::Mesh | AssetType::MeshSupplementary => {
+                let Ok(contents) = std::fs::read(project_root.join(&asset.path)) else {
+                    continue;
+                };
+                let dependency_ids = process_mesh_file(&path, contents, &asset_paths).dependencies.into_iter()
+                    .filter_map(|dependency_path| assets_by_path.get(&dependency_path))
+                    .map(|dependency| (dependency.id, used_by.clone()));
+                pending_assets.extend(dependency_ids);
+            },
+            _ => {},
+        }
+    }
+
+    match diagnostics.is_empty() {
+        true => Ok(used_asset_ids),
+        false => Err(BuildError::Failed { diagnostics }),
+    }
+}
+
+/// An entry in `includeAssets` or `excludeAssets`: an asset ID, the path of an asset, a folder (ending in `/`),
+/// or a glob matching asset paths (e.g. `textures/**/*.png`)
+struct AssetPattern<'a> {
+    entry: &'a str,
+    /// `None` if the entry is not a glob
+    glob: Option<GlobMatcher>,
+}
+
+impl<'a> AssetPattern<'a> {
+    fn new(entry: &'a str) -> Result<Self, String> {
+        let glob = match entry.contains(['*', '?', '[', '{']) {
+            // @NOTE `*` only matches within a single folder, while `**` matches any number of folders
+            true => Some(GlobBuilder::new(entry).literal_separator(true).build().map_err(|error| error.to_string())?.compile_matcher()),
+            false => None,
+        };
+        Ok(Self { entry, glob })
+    }
+
+    fn matches(&self, asset: &AssetDefinition) -> bool {
+        let path = get_normalized_path(&asset.path);
+        match &self.glob {
+            Some(glob) => glob.is_match(&path),
+            None => asset.id.to_string() == self.entry
+                || path == self.entry
+                || (self.entry.ends_with('/') && path.starts_with(self.entry)),
+        }
+    }
+}
+
+/// Parse each entry in `includeAssets` or `excludeAssets` (named by `field_name`), adding a diagnostic for each invalid entry
+fn parse_asset_patterns<'a>(
+    entries: &'a [String],
+    field_name: &str,
+    project_file_name: &Path,
+    diagnostics: &mut Vec<BuildDiagnostic>,
+) -> Vec<AssetPattern<'a>> {
+    let mut patterns = Vec::new();
+    for entry in entries.iter() {
+        match AssetPattern::new(entry) {
+            Ok(pattern) => patterns.push(pattern),
+            Err(error) => diagnostics.push(BuildDiagnostic::error(project_file_name, format!("Invalid entry '{entry}' in `{field_name}`: {error}"))),
+        }
+    }
+    patterns
+}
+
+/// Module specifiers of every module a script imports at runtime. Empty if the script cannot be read or parsed.
+pub fn find_script_imports(project_root: &Path, script_path: &Path, compiler: &CompilerConfig) -> Vec<String> {
+    let path = project_root.join(script_path);
+    let Ok(file_contents) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+
+    let cm = Lrc::new(SourceMap::new(swc_common::FilePathMapping::empty()));
+    let source = cm.new_source_file(FileName::Real(path).into(), file_contents);
+    match swc_ecma_parser::parse_file_as_module(
+        &source,
+        compiler.script_syntax(script_path),
+        compiler.target,
+        None,
+        &mut Vec::new(),
+    ) {
+        Ok(module) => find_imports(&cm, &module, compiler.script_syntax(script_path)).into_iter().map(|import| import.specifier).collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// An asset's path as a string, with `/` as the separator
+fn get_normalized_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
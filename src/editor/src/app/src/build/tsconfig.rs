@@ -0,0 +1,145 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use super::core_modules::CORE_MODULES;
+use super::diagnostics::BuildDiagnostic;
+use super::imports::SCRIPT_PATH_PREFIX;
+
+// Constants
+/// The project's TypeScript config, in the project root
+pub const TSCONFIG_FILE_NAME: &str = "tsconfig.json";
+/// Extensions that an alias may (redundantly) include e.g. `scripts/player.ts`
+const SCRIPT_EXTENSIONS: [&str; 4] = [".ts", ".tsx", ".js", ".jsx"];
+
+// Types
+/// Aliases for scripts, from the `baseUrl` and `paths` compiler options of the project's `tsconfig.json`.
+/// These let scripts import each other by a non-relative specifier e.g. `import { Player } from '@game/player'`.
+/// @TODO Read options from configs that `tsconfig.json` `extends`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PathAliases {
+    /// Folder that non-relative specifiers are resolved from (relative to the project root e.g. `scripts`)
+    base_url: Option<String>,
+    /// Each pattern (e.g. `@game/*`) with the paths it maps to (relative to the project root e.g. `scripts/*`)
+    paths: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct TsConfig {
+    compiler_options: TsConfigCompilerOptions,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct TsConfigCompilerOptions {
+    base_url: Option<String>,
+    paths: BTreeMap<String, Vec<String>>,
+}
+
+impl PathAliases {
+    /// Read the aliases in the project's `tsconfig.json`. There are no aliases if the project does not have one.
+    pub fn read(project_root: &Path) -> Result<Self, BuildDiagnostic> {
+        let Ok(jsonc) = std::fs::read_to_string(project_root.join(TSCONFIG_FILE_NAME)) else {
+            return Ok(Self::default());
+        };
+        let tsconfig: TsConfig = jsonc_parser::parse_to_serde_value(&jsonc, &Default::default())
+            .map_err(|error| error.to_string())
+            .and_then(|value| serde_json::from_value(value.unwrap_or_default()).map_err(|error| error.to_string()))
+            .map_err(|error| BuildDiagnostic::error(TSCONFIG_FILE_NAME, format!("Failed to read TypeScript config: {error}")))?;
+
+        // @NOTE `paths` are relative to `baseUrl` if it is set, otherwise to the tsconfig (i.e. the project root)
+        let base_url = tsconfig.compiler_options.base_url
+            .map(|base_url| normalize_path("", &base_url)
+                .ok_or_else(|| BuildDiagnostic::error(TSCONFIG_FILE_NAME, format!("`baseUrl` '{base_url}' is outside the project"))))
+            .transpose()?;
+        let paths_root = base_url.clone().unwrap_or_default();
+        let paths = tsconfig.compiler_options.paths.into_iter()
+            .map(|(pattern, targets)| {
+                // @NOTE Targets outside the project (e.g. the engine's type declarations) can never be scripts
+                let targets = targets.iter()
+                    .filter_map(|target| normalize_path(&paths_root, target))
+                    .collect();
+                (pattern, targets)
+            })
+            .collect();
+
+        Ok(Self { base_url, paths })
+    }
+
+    /// Module ID of the script that a non-relative specifier is an alias of e.g. `@game/player` => `./player`.
+    /// `is_script` is whether a module ID belongs to a script in the build.
+    /// Returns `None` if the specifier does not resolve to a script.
+    /// @NOTE Core modules are never aliases, as tsconfigs map them to the engine's type declarations
+    pub fn resolve(&self, specifier: &str, is_script: impl Fn(&str) -> bool) -> Option<String> {
+        if CORE_MODULES.contains(&specifier) {
+            return None;
+        }
+
+        // @NOTE Like TypeScript, only the matching pattern with the longest prefix is used
+        let path_candidates = self.find_pattern(specifier)
+            .into_iter()
+            .flat_map(|(pattern, targets)| {
+                let wildcard = get_wildcard_match(pattern, specifier).unwrap_or_default();
+                targets.iter().map(move |target| target.replacen('*', wildcard, 1))
+            });
+        let base_url_candidate = self.base_url.as_deref()
+            .and_then(|base_url| normalize_path(base_url, specifier));
+
+        path_candidates
+            .chain(base_url_candidate)
+            .flat_map(|path| get_candidate_module_ids(&path))
+            .find(|module_id| is_script(module_id))
+    }
+
+    /// Whether a specifier matches one of the patterns in `paths`, for reporting aliases that do not resolve
+    pub fn matches(&self, specifier: &str) -> bool {
+        self.find_pattern(specifier).is_some()
+    }
+
+    fn find_pattern(&self, specifier: &str) -> Option<(&String, &Vec<String>)> {
+        self.paths.iter()
+            .filter(|(pattern, _)| get_wildcard_match(pattern, specifier).is_some())
+            .max_by_key(|(pattern, _)| pattern.find('*').unwrap_or(pattern.len()))
+    }
+}
+
+/// The part of `specifier` matched by the `*` in `pattern` (or an empty string if the pattern has no `*`).
+/// `None` if the specifier does not match.
+fn get_wildcard_match<'a>(pattern: &str, specifier: &'a str) -> Option<&'a str> {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => specifier.strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(suffix)),
+        None => (pattern == specifier).then_some(""),
+    }
+}
+
+/// Module IDs that a project-relative path could refer to: the path itself,
+/// the path without a script extension, or an `index` script in the folder at the path.
+fn get_candidate_module_ids(path: &str) -> Vec<String> {
+    let Some(relative_path) = path.strip_prefix(SCRIPT_PATH_PREFIX) else {
+        return Vec::new();
+    };
+    let mut module_ids = vec![format!("./{relative_path}")];
+    if let Some(without_extension) = SCRIPT_EXTENSIONS.iter().find_map(|extension| relative_path.strip_suffix(extension)) {
+        module_ids.push(format!("./{without_extension}"));
+    }
+    module_ids.push(format!("./{relative_path}/index"));
+    module_ids
+}
+
+/// Join `path` onto the project-relative folder `root`, resolving any `.` and `..` segments.
+/// Returns `None` if the result is outside the project.
+pub fn normalize_path(root: &str, path: &str) -> Option<String> {
+    let mut segments: Vec<&str> = root.split('/').filter(|segment| !segment.is_empty()).collect();
+    for segment in path.split(['/', '\\']) {
+        match segment {
+            "." | "" => {},
+            ".." => {
+                segments.pop()?;
+            },
+            segment => segments.push(segment),
+        }
+    }
+    Some(segments.join("/"))
+}
@@ -0,0 +1,89 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::filesystem::project::ProjectDefinition;
+use super::compression::CompressionPolicy;
+use super::diagnostics::{BuildDiagnostic, BuildError};
+use super::BuildProfile;
+
+// Types
+/// A named subset of a project to build e.g. a demo with only the first few scenes.
+/// Read from the `variants` section of the project file, and chosen with [`super::BuildOptions::variant`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BuildVariant {
+    /// Scenes to include, each given by its ID or its name in the project's scene list.
+    /// Every scene is included if this is not given.
+    pub scenes: Option<Vec<String>>,
+    /// Assets to include as well as those listed in the project's `includeAssets` (in the same format)
+    pub include_assets: Vec<String>,
+    /// Assets to leave out (in the same format as `include_assets`).
+    /// The build fails if anything that is included uses one of them.
+    pub exclude_assets: Vec<String>,
+    /// Overrides the profile the build was started with
+    pub profile: Option<BuildProfile>,
+    /// Overrides the project's compression policy
+    pub compression: Option<CompressionPolicy>,
+    /// Defines to add to (or override) the project's `defines` (see [`super::defines`])
+    pub defines: HashMap<String, Value>,
+}
+
+/// Look up a build variant by name in the project file
+pub fn get_variant<'a>(
+    project_definition: &'a ProjectDefinition,
+    variant_name: &str,
+    project_file_name: &Path,
+) -> Result<&'a BuildVariant, BuildError> {
+    project_definition.variants.get(variant_name).ok_or_else(|| {
+        let mut variant_names: Vec<&str> = project_definition.variants.keys().map(String::as_str).collect();
+        variant_names.sort();
+        let message = match variant_names.is_empty() {
+            true => format!("No build variant named '{variant_name}'. The project does not define any `variants`"),
+            false => format!("No build variant named '{variant_name}'. Expected one of: {}", variant_names.join(", ")),
+        };
+        BuildError::single(BuildDiagnostic::error(project_file_name, message))
+    })
+}
+
+/// IDs of the scenes a variant includes. `None` if it includes every scene.
+/// Fails if any scene it lists is not one of the project's scenes.
+pub fn get_included_scene_ids(
+    project_definition: &ProjectDefinition,
+    variant_name: &str,
+    variant: &BuildVariant,
+    project_file_name: &Path,
+) -> Result<Option<HashSet<Uuid>>, BuildError> {
+    let Some(scenes) = &variant.scenes else {
+        return Ok(None);
+    };
+    if scenes.is_empty() {
+        return Err(BuildError::single(BuildDiagnostic::error(project_file_name, format!("Variant '{variant_name}' does not include any scenes"))));
+    }
+
+    let mut diagnostics = Vec::new();
+    let mut scene_ids = HashSet::new();
+    for scene in scenes.iter() {
+        let by_name = project_definition.scene_list.iter()
+            .find(|named_scene| named_scene.name == *scene)
+            .map(|named_scene| named_scene.id);
+        let by_id = Uuid::parse_str(scene).ok()
+            .filter(|scene_id| project_definition.scenes.iter().any(|project_scene| project_scene.id == *scene_id));
+        match by_name.or(by_id) {
+            Some(scene_id) => {
+                scene_ids.insert(scene_id);
+            },
+            None => diagnostics.push(BuildDiagnostic::error(
+                project_file_name,
+                format!("Variant '{variant_name}' includes scene '{scene}' - it isn't the ID of one of the project's scenes, or a name in `sceneList`"),
+            )),
+        }
+    }
+
+    match diagnostics.is_empty() {
+        true => Ok(Some(scene_ids)),
+        false => Err(BuildError::Failed { diagnostics }),
+    }
+}
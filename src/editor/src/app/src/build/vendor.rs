@@ -0,0 +1,247 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::path::Path;
+use swc_common::{source_map::SourceMap, sync::Lrc, FileName};
+use swc_ecma_ast::ModuleItem;
+use swc_ecma_parser::Syntax;
+
+use super::core_modules::CORE_MODULES;
+use super::diagnostics::{BuildDiagnostic, BuildError};
+use super::imports::{self, get_module_id, is_relative_specifier};
+use super::tree_shaking::find_script_imports;
+use super::tsconfig::normalize_path;
+use super::BuildOptions;
+
+// Constants
+/// Folder (in the project root) that npm installs packages into
+pub const NODE_MODULES_FOLDER: &str = "node_modules";
+/// Extensions tried, in order, for a module imported without one e.g. `./easing` => `./easing.js`
+const MODULE_EXTENSIONS: [&str; 2] = ["js", "mjs"];
+/// Conditions in a package's `exports` that the web player satisfies, in order of preference.
+/// @NOTE Node uses the first condition in the order the package lists them, but that order is lost when `package.json` is parsed
+const EXPORT_CONDITIONS: [&str; 4] = ["browser", "import", "module", "default"];
+
+// Types
+/// The parts of a package's `package.json` used to find its modules
+#[derive(Deserialize)]
+struct PackageJson {
+    module: Option<String>,
+    main: Option<String>,
+    exports: Option<Value>,
+}
+
+/// Syntax that modules from packages are parsed with i.e. plain JavaScript
+pub fn syntax() -> Syntax {
+    Syntax::Es(Default::default())
+}
+
+/// Whether a file (relative to the project root) is a module from an npm package
+pub fn is_vendor_path(file_path: &str) -> bool {
+    file_path.replace('\\', "/").starts_with(&format!("{NODE_MODULES_FOLDER}/"))
+}
+
+/// Find every module from an npm package that the game's scripts import, either directly
+/// or through other modules (e.g. `import { easeInOut } from 'easing-utils'`).
+/// `script_paths` are the (project-relative) paths of every script in the build.
+/// Returns the (project-relative) paths of those modules, sorted.
+/// Fails if any of them cannot be found, or are not ES modules (which is all `ScriptLoader` can load once compiled).
+pub fn find_vendor_files(project_root: &Path, script_paths: &[&Path], options: &BuildOptions) -> Result<Vec<String>, BuildError> {
+    let script_module_ids: HashSet<String> = script_paths.iter()
+        .filter_map(|script_path| get_module_id(script_path.to_str()?))
+        .collect();
+
+    // Imports of packages from scripts
+    // @NOTE Each pending import is paired with the path of the file it is in
+    let mut pending_imports: VecDeque<(String, String)> = VecDeque::new();
+    for script_path in script_paths.iter() {
+        let specifiers = find_script_imports(project_root, script_path, &options.compiler);
+        let importer = script_path.to_string_lossy().replace('\\', "/");
+        for specifier in specifiers {
+            let is_alias = options.path_aliases.resolve(&specifier, |module_id| script_module_ids.contains(module_id)).is_some();
+            if is_package_specifier(&specifier) && !is_alias {
+                pending_imports.push_back((importer.clone(), specifier));
+            }
+        }
+    }
+
+    // Follow imports between modules
+    let mut diagnostics = Vec::new();
+    let mut visited_imports = HashSet::new();
+    let mut vendor_files = BTreeSet::new();
+    while let Some((importer, specifier)) = pending_imports.pop_front() {
+        if !visited_imports.insert((importer.clone(), specifier.clone())) {
+            continue;
+        }
+        let vendor_file = match resolve_import(project_root, &importer, &specifier) {
+            Ok(vendor_file) => vendor_file,
+            Err(error) => {
+                diagnostics.push(BuildDiagnostic::error(&importer, error));
+                continue;
+            },
+        };
+        if !vendor_files.insert(vendor_file.clone()) {
+            continue;
+        }
+
+        match read_vendor_module_imports(project_root, &vendor_file, options) {
+            Ok(specifiers) => pending_imports.extend(specifiers.into_iter()
+                .filter(|specifier| !CORE_MODULES.contains(&specifier.as_str()))
+                .map(|specifier| (vendor_file.clone(), specifier))),
+            Err(error) => diagnostics.push(BuildDiagnostic::error(&vendor_file, error)),
+        }
+    }
+
+    match diagnostics.is_empty() {
+        true => Ok(vendor_files.into_iter().collect()),
+        false => Err(BuildError::Failed { diagnostics }),
+    }
+}
+
+/// Module ID of each import of a package in a file (and, for modules from packages, of each relative import),
+/// keyed by the specifier as written e.g. `easing-utils` => `node_modules/easing-utils/dist/index.js`.
+/// The ID of a module from a package is its path within the cartridge.
+/// Imports that cannot be resolved are left out (they are reported by [`find_vendor_files`]).
+pub fn resolve_vendor_imports<'a>(
+    project_root: &Path,
+    file_path: &str,
+    specifiers: impl IntoIterator<Item = &'a str>,
+) -> BTreeMap<String, String> {
+    let file_path = file_path.replace('\\', "/");
+    specifiers.into_iter()
+        .filter(|specifier| is_package_specifier(specifier) || (is_vendor_path(&file_path) && is_relative_specifier(specifier)))
+        .filter_map(|specifier| Some((specifier.to_string(), resolve_import(project_root, &file_path, specifier).ok()?)))
+        .collect()
+}
+
+/// Whether a specifier refers to an npm package e.g. `easing-utils`, `@scope/noise/simplex`
+fn is_package_specifier(specifier: &str) -> bool {
+    !is_relative_specifier(specifier) && !specifier.starts_with('/') && !CORE_MODULES.contains(&specifier)
+}
+
+/// Path (relative to the project root) of the module that an import refers to.
+/// `importer` is the (project-relative) path of the file the import is in.
+fn resolve_import(project_root: &Path, importer: &str, specifier: &str) -> Result<String, String> {
+    if is_relative_specifier(specifier) {
+        let (importer_folder, _) = importer.rsplit_once('/').unwrap_or_default();
+        return normalize_path(importer_folder, specifier)
+            .and_then(|path| resolve_file(project_root, &path))
+            .filter(|path| is_vendor_path(path))
+            .ok_or_else(|| format!("Cannot find module '{specifier}'"));
+    }
+
+    // e.g. `@scope/noise/simplex` => (`@scope/noise`, `simplex`)
+    let mut segments = specifier.splitn(if specifier.starts_with('@') { 3 } else { 2 }, '/');
+    let package_name = match specifier.starts_with('@') {
+        true => format!("{}/{}", segments.next().unwrap_or_default(), segments.next().unwrap_or_default()),
+        false => segments.next().unwrap_or_default().to_string(),
+    };
+    let subpath = segments.next().unwrap_or_default();
+
+    let package_folder = find_package_folder(project_root, importer, &package_name)
+        .ok_or_else(|| format!("Cannot find package '{package_name}'. Install it in the project folder with `npm install {package_name}`"))?;
+    let package_json: PackageJson = std::fs::read_to_string(project_root.join(&package_folder).join("package.json"))
+        .map_err(|error| error.to_string())
+        .and_then(|contents| serde_json::from_str(&contents).map_err(|error| error.to_string()))
+        .map_err(|error| format!("Failed to read package.json of package '{package_name}': {error}"))?;
+
+    let target = match &package_json.exports {
+        Some(exports) => {
+            let subpath = match subpath.is_empty() {
+                true => String::from("."),
+                false => format!("./{subpath}"),
+            };
+            resolve_exports(exports, &subpath)
+                .ok_or_else(|| format!("Package '{package_name}' does not export '{subpath}' for browsers or ES modules"))?
+        },
+        None if subpath.is_empty() => package_json.module.or(package_json.main).unwrap_or_else(|| String::from("index.js")),
+        None => subpath.to_string(),
+    };
+    normalize_path(&package_folder, &target)
+        .and_then(|path| resolve_file(project_root, &path))
+        .filter(|path| path.starts_with(&package_folder))
+        .ok_or_else(|| format!("Cannot find module '{specifier}' in package '{package_name}'"))
+}
+
+/// Folder (relative to the project root) of an installed package, as seen from the file at `importer`.
+/// @NOTE Like Node, packages installed within another package (e.g. a different version of a shared dependency) are found first
+fn find_package_folder(project_root: &Path, importer: &str, package_name: &str) -> Option<String> {
+    let mut folder_segments: Vec<&str> = importer.split('/').collect();
+    folder_segments.pop();
+    loop {
+        let is_in_packages = folder_segments.first() == Some(&NODE_MODULES_FOLDER) && folder_segments.last() != Some(&NODE_MODULES_FOLDER);
+        if folder_segments.is_empty() || is_in_packages {
+            let package_folder = folder_segments.iter()
+                .chain([NODE_MODULES_FOLDER, package_name].iter())
+                .copied()
+                .collect::<Vec<_>>()
+                .join("/");
+            if project_root.join(&package_folder).join("package.json").is_file() {
+                return Some(package_folder);
+            }
+        }
+        folder_segments.pop()?;
+    }
+}
+
+/// Resolve a subpath (e.g. `.` or `./simplex`) using a package's `exports` (see [`EXPORT_CONDITIONS`]).
+/// Returns the path of the module relative to the package, or `None` if the subpath is not exported.
+fn resolve_exports(exports: &Value, subpath: &str) -> Option<String> {
+    // @NOTE `exports` can either map subpaths to targets, or be the target of the `.` subpath itself
+    let subpaths = match exports {
+        Value::Object(subpaths) if subpaths.keys().any(|key| key.starts_with('.')) => subpaths,
+        exports => return (subpath == ".").then(|| resolve_export_target(exports, "")).flatten(),
+    };
+    if let Some(target) = subpaths.get(subpath) {
+        return resolve_export_target(target, "");
+    }
+
+    // Patterns e.g. `"./features/*": "./dist/features/*.js"`, using the one with the longest prefix
+    subpaths.iter()
+        .filter_map(|(pattern, target)| {
+            let (prefix, suffix) = pattern.split_once('*')?;
+            let wildcard = subpath.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some((prefix.len(), target, wildcard))
+        })
+        .max_by_key(|(prefix_length, _, _)| *prefix_length)
+        .and_then(|(_, target, wildcard)| resolve_export_target(target, wildcard))
+}
+
+/// Resolve a target in a package's `exports`, which is either a path, conditions, or a list of fallbacks
+fn resolve_export_target(target: &Value, wildcard: &str) -> Option<String> {
+    match target {
+        Value::String(path) => Some(path.replace('*', wildcard)),
+        Value::Object(conditions) => EXPORT_CONDITIONS.iter()
+            .filter_map(|condition| conditions.get(*condition))
+            .find_map(|target| resolve_export_target(target, wildcard)),
+        Value::Array(targets) => targets.iter().find_map(|target| resolve_export_target(target, wildcard)),
+        // @NOTE `null` means the subpath is not exported
+        _ => None,
+    }
+}
+
+/// Find the file a (project-relative) module path refers to: the path itself, the path with a module extension,
+/// or an `index` module in the folder at the path
+fn resolve_file(project_root: &Path, path: &str) -> Option<String> {
+    std::iter::once(path.to_string())
+        .chain(MODULE_EXTENSIONS.iter().map(|extension| format!("{path}.{extension}")))
+        .chain(MODULE_EXTENSIONS.iter().map(|extension| format!("{path}/index.{extension}")))
+        .find(|path| project_root.join(path).is_file())
+}
+
+/// Read a module from a package and find every module it imports. Fails if it is not an ES module.
+fn read_vendor_module_imports(project_root: &Path, file_path: &str, options: &BuildOptions) -> Result<Vec<String>, String> {
+    let path = project_root.join(file_path);
+    let file_contents = std::fs::read_to_string(&path)
+        .map_err(|error| format!("Failed to read file: {error}"))?;
+
+    let cm = Lrc::new(SourceMap::new(swc_common::FilePathMapping::empty()));
+    let source = cm.new_source_file(FileName::Real(path).into(), file_contents);
+    let module = swc_ecma_parser::parse_file_as_module(&source, syntax(), options.compiler.target, None, &mut Vec::new())
+        .map_err(|error| format!("Failed to parse module: {}", error.kind().msg()))?;
+    // @NOTE CommonJS modules (i.e. using `require()` and `module.exports`) have no `import` or `export` declarations
+    if !module.body.iter().any(|item| matches!(item, ModuleItem::ModuleDecl(_))) {
+        return Err(String::from("Not an ES module. Only packages that include ES modules can be used in scripts"));
+    }
+    Ok(imports::find_imports(&cm, &module, syntax()).into_iter().map(|import| import.specifier).collect())
+}
@@ -0,0 +1,11 @@
+pub mod header;
+pub mod integrity;
+pub mod manifest;
+pub mod reader;
+
+/// Name of the manifest file within a cartridge archive - Should be kept in-sync with `CARTRIDGE_MANIFEST_FILENAME` in the runtime
+pub const CARTRIDGE_MANIFEST_FILENAME: &str = "manifest.json";
+/// Name of the header file within a cartridge archive - Should be kept in-sync with `CARTRIDGE_HEADER_FILENAME` in the runtime
+pub const CARTRIDGE_HEADER_FILENAME: &str = "header.json";
+/// Name of the (optional) signature file within a cartridge archive. Signs the exact bytes of the manifest file.
+pub const CARTRIDGE_SIGNATURE_FILENAME: &str = "signature.json";
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+use crate::build::BuildProfile;
+
+// Constants
+/// Version of the cartridge format written by the editor. Must be incremented whenever the contents of a cartridge
+/// change in a way that older players cannot load - Should be kept in-sync with `CARTRIDGE_FORMAT_VERSION` in the runtime
+pub const CARTRIDGE_FORMAT_VERSION: u32 = 1;
+/// Version of the engine (i.e. the runtime) that the editor builds cartridges for - Should be kept in-sync with
+/// `ENGINE_VERSION` in the runtime
+pub const ENGINE_VERSION: &str = "0.1.0";
+
+// Types
+/// Metadata identifying a cartridge, stored separately from its manifest so that it can be read
+/// (and incompatible cartridges rejected) without loading the rest of the cartridge.
+/// This mirrors the `CartridgeHeader` type in the runtime and should be kept in-sync with it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartridgeHeader {
+    /// Version of the format of the cartridge's contents. See [`CARTRIDGE_FORMAT_VERSION`].
+    pub format_version: u32,
+    /// Title of the game
+    pub title: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// Version of the game e.g. `1.2.0`
+    pub game_version: Option<String>,
+    /// Oldest version of the engine that can play the cartridge e.g. `0.1.0`
+    pub min_engine_version: String,
+    /// When the cartridge was built, in seconds since the Unix epoch
+    pub build_timestamp: u64,
+    pub build_profile: BuildProfile,
+    /// Name of the build variant the cartridge was built from (e.g. `demo`). `None` if the whole project was built.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub build_variant: Option<String>,
+}
+
+/// Parse an engine version (e.g. `0.1.0`) into its major, minor and patch numbers.
+/// Returns `None` if the version is not in that format.
+pub fn parse_engine_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version.trim().split('.').map(|part| part.parse::<u32>().ok());
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Some(major)), Some(Some(minor)), Some(Some(patch)), None) => Some((major, minor, patch)),
+        _ => None,
+    }
+}
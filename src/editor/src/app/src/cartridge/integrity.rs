@@ -0,0 +1,238 @@
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::io::{Read, Seek};
+use std::path::Path;
+use zip::ZipArchive;
+
+use crate::filesystem::get_data_hash;
+use super::manifest::{ArchiveEntryHash, CartridgeArchiveManifest};
+use super::reader::read_archive_file;
+use super::{CARTRIDGE_MANIFEST_FILENAME, CARTRIDGE_SIGNATURE_FILENAME};
+
+// Types
+/// Signature of a cartridge's manifest. Since the manifest contains the hash of every other file
+/// in the cartridge, this signs the entire cartridge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartridgeSignature {
+    /// Hex-encoded ed25519 public key of the key the manifest was signed with
+    pub public_key: String,
+    /// Hex-encoded ed25519 signature of the manifest file's bytes
+    pub signature: String,
+}
+
+/// Result of checking a cartridge's contents against the hashes in its manifest
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartridgeVerification {
+    pub signature: SignatureStatus,
+    /// Number of files whose contents match the manifest
+    pub verified_entry_count: usize,
+    /// Files that do not match the manifest
+    pub failed_entries: Vec<FailedEntry>,
+}
+
+impl CartridgeVerification {
+    /// Whether every file matches the manifest, and the manifest's signature (if any) is valid
+    pub fn is_valid(&self) -> bool {
+        self.failed_entries.is_empty() && matches!(self.signature, SignatureStatus::Unsigned | SignatureStatus::Valid { .. })
+    }
+}
+
+/// Whether a cartridge's manifest is signed, and if so, whether it can be trusted
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SignatureStatus {
+    /// The cartridge has no signature
+    Unsigned,
+    /// The manifest was signed by the key with this public key
+    #[serde(rename_all = "camelCase")]
+    Valid { public_key: String },
+    /// The signature is valid, but was not made by the expected key
+    #[serde(rename_all = "camelCase")]
+    Untrusted { public_key: String },
+    /// The signature does not match the manifest (or cannot be read)
+    #[serde(rename_all = "camelCase")]
+    Invalid { reason: String },
+}
+
+/// A file in a cartridge that does not match the cartridge's manifest
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedEntry {
+    pub path: String,
+    pub problem: EntryProblem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EntryProblem {
+    /// The file is listed in the manifest, but is not in the cartridge
+    Missing,
+    /// The file's contents do not match the hashes in the manifest
+    Modified,
+    /// The file is in the cartridge, but is not listed in the manifest
+    Unlisted,
+}
+
+/// Compute the hashes of a file's (uncompressed) contents, to be stored in a cartridge's manifest
+pub fn hash_entry(path: &str, data: &[u8]) -> ArchiveEntryHash {
+    ArchiveEntryHash {
+        path: path.to_string(),
+        size: data.len() as u64,
+        xxh3: get_data_hash(data),
+        sha256: to_hex(&Sha256::digest(data)),
+    }
+}
+
+/// Sign the bytes of a cartridge's manifest file
+pub fn sign_manifest(manifest_file_bytes: &[u8], signing_key: &SigningKey) -> CartridgeSignature {
+    CartridgeSignature {
+        public_key: to_hex(signing_key.verifying_key().as_bytes()),
+        signature: to_hex(&signing_key.sign(manifest_file_bytes).to_bytes()),
+    }
+}
+
+/// Read an ed25519 signing key from a file. The file contains the hex-encoded 32-byte secret key.
+pub fn read_signing_key(path: &Path) -> Result<SigningKey, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|error| format!("Failed to read signing key: {error}"))?;
+    let secret_key: [u8; 32] = from_hex(contents.trim())
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| String::from("Signing key file is not a hex-encoded 32-byte ed25519 key"))?;
+    Ok(SigningKey::from_bytes(&secret_key))
+}
+
+/// Generate a new ed25519 signing key and write it to a file (see [`read_signing_key`]).
+/// Will not overwrite an existing file.
+pub fn generate_signing_key(path: &Path) -> Result<SigningKey, String> {
+    if path.exists() {
+        return Err(format!("Will not overwrite existing file: {}", path.display()));
+    }
+
+    let signing_key = SigningKey::generate(&mut OsRng);
+    std::fs::write(path, to_hex(signing_key.as_bytes()))
+        .map_err(|error| format!("Failed to write signing key: {error}"))?;
+    Ok(signing_key)
+}
+
+/// Hex-encoded public key of a signing key, for sharing with anybody verifying cartridges signed with it
+pub fn get_public_key(signing_key: &SigningKey) -> String {
+    to_hex(signing_key.verifying_key().as_bytes())
+}
+
+/// Check every file in a cartridge against the hashes in its manifest, and check the manifest's signature (if any).
+/// If `trusted_public_key` (hex-encoded) is given, the manifest must be signed by that key.
+/// Returns an error if the cartridge cannot be read, or has no hashes to check against.
+pub fn verify_cartridge<R: Read + Seek>(reader: R, trusted_public_key: Option<&str>) -> Result<CartridgeVerification, String> {
+    let mut archive = ZipArchive::new(reader).map_err(|error| format!("Failed to read cartridge: {error}"))?;
+
+    let manifest_file_bytes = read_archive_file(&mut archive, CARTRIDGE_MANIFEST_FILENAME)?
+        .ok_or_else(|| format!("Cartridge has no {CARTRIDGE_MANIFEST_FILENAME}"))?;
+    let manifest: CartridgeArchiveManifest = serde_json::from_slice(&manifest_file_bytes)
+        .map_err(|error| format!("Failed to parse {CARTRIDGE_MANIFEST_FILENAME}: {error}"))?;
+    if manifest.entries.is_empty() {
+        return Err(String::from("Cartridge has no integrity hashes. It was built by an older version of PolyZone"));
+    }
+
+    // Check signature
+    let signature = match read_archive_file(&mut archive, CARTRIDGE_SIGNATURE_FILENAME)? {
+        Some(signature_file_bytes) => verify_signature(&manifest_file_bytes, &signature_file_bytes, trusted_public_key),
+        None if trusted_public_key.is_some() => SignatureStatus::Invalid {
+            reason: String::from("Cartridge is not signed"),
+        },
+        None => SignatureStatus::Unsigned,
+    };
+
+    // Check every file listed in the manifest
+    let mut verified_entry_count = 0;
+    let mut failed_entries = Vec::new();
+    for entry in manifest.entries.iter() {
+        let problem = match read_archive_file(&mut archive, &entry.path) {
+            Ok(Some(data)) if hash_entry(&entry.path, &data) == *entry => None,
+            Ok(Some(_)) => Some(EntryProblem::Modified),
+            Ok(None) => Some(EntryProblem::Missing),
+            // @NOTE e.g. the compressed data is corrupt
+            Err(_) => Some(EntryProblem::Modified),
+        };
+        match problem {
+            Some(problem) => failed_entries.push(FailedEntry {
+                path: entry.path.clone(),
+                problem,
+            }),
+            None => verified_entry_count += 1,
+        }
+    }
+
+    // Check for files that are not in the manifest
+    let listed_paths: HashSet<&str> = manifest.entries.iter()
+        .map(|entry| entry.path.as_str())
+        .chain([CARTRIDGE_MANIFEST_FILENAME, CARTRIDGE_SIGNATURE_FILENAME])
+        .collect();
+    // @NOTE Checked in archive order (rather than `file_names()`) so the report is always in the same order
+    for index in 0..archive.len() {
+        let Some(path) = archive.name_for_index(index) else {
+            continue;
+        };
+        // @NOTE Directory entries (e.g. added when a cartridge is re-zipped by hand) have no contents to check
+        if !listed_paths.contains(path) && !path.ends_with('/') {
+            failed_entries.push(FailedEntry {
+                path: path.to_string(),
+                problem: EntryProblem::Unlisted,
+            });
+        }
+    }
+
+    Ok(CartridgeVerification {
+        signature,
+        verified_entry_count,
+        failed_entries,
+    })
+}
+
+fn verify_signature(manifest_file_bytes: &[u8], signature_file_bytes: &[u8], trusted_public_key: Option<&str>) -> SignatureStatus {
+    let invalid = |reason: &str| SignatureStatus::Invalid { reason: reason.to_string() };
+
+    let Ok(signature) = serde_json::from_slice::<CartridgeSignature>(signature_file_bytes) else {
+        return invalid("Cannot parse signature");
+    };
+    let Some(public_key) = from_hex(&signature.public_key)
+        .and_then(|bytes| bytes.try_into().ok())
+        .and_then(|bytes: [u8; 32]| VerifyingKey::from_bytes(&bytes).ok())
+    else {
+        return invalid("Signature has an invalid public key");
+    };
+    let Some(signature_bytes) = from_hex(&signature.signature)
+        .and_then(|bytes| bytes.try_into().ok())
+        .map(|bytes: [u8; 64]| Signature::from_bytes(&bytes))
+    else {
+        return invalid("Cannot parse signature");
+    };
+
+    if public_key.verify(manifest_file_bytes, &signature_bytes).is_err() {
+        return invalid("Signature does not match the manifest");
+    }
+
+    let public_key = to_hex(public_key.as_bytes());
+    match trusted_public_key {
+        Some(trusted_public_key) if !trusted_public_key.trim().eq_ignore_ascii_case(&public_key) => SignatureStatus::Untrusted { public_key },
+        _ => SignatureStatus::Valid { public_key },
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(hex.get(index..index + 2)?, 16).ok())
+        .collect()
+}
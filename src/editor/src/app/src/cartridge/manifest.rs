@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uuid::Uuid;
+
+use crate::filesystem::assets::AssetType;
+
+// @NOTE Types for the contents of a cartridge's `manifest.json`.
+// These mirror the types in the runtime (`@polyzone/runtime/src/cartridge/archive`) and should be kept in-sync with them.
+
+/// The raw manifest of a cartridge archive, containing all the content in the cartridge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartridgeArchiveManifest {
+    /// Every scene in the cartridge. The entry scene is always first.
+    pub scenes: Vec<SceneDefinition>,
+    pub assets: Vec<AssetDefinition>,
+    /// Path of the scene the game starts in. `None` for cartridges built before entry scenes were recorded
+    /// (which start in the first scene).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_scene: Option<PathBuf>,
+    /// Logical names for scenes, in the order the project lists them, for switching between scenes at runtime
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scene_list: Vec<NamedScene>,
+    /// Paths of modules from npm packages that scripts import (compiled like scripts), which are also their module IDs.
+    /// They are loaded before any scripts.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub vendor_modules: Vec<String>,
+    /// Hashes of every other file in the cartridge, used to detect corrupt or modified cartridges.
+    /// Empty for cartridges built before hashes were recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<ArchiveEntryHash>,
+    /// Files with exactly the same contents as another file in the cartridge, which are only stored once
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<ArchiveEntryAlias>,
+}
+
+/// A logical name for a scene in the cartridge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedScene {
+    pub name: String,
+    /// Path of the scene (see [`SceneDefinition::path`])
+    pub path: PathBuf,
+}
+
+/// Hashes of a single file within the cartridge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntryHash {
+    /// Path of the file within the cartridge
+    pub path: String,
+    /// Size (in bytes) of the file, uncompressed
+    pub size: u64,
+    /// xxHash3 (64-bit) of the file's uncompressed contents. Quick to check, for detecting corruption.
+    pub xxh3: String,
+    /// SHA-256 of the file's uncompressed contents. Covered by the cartridge's signature, for detecting tampering.
+    pub sha256: String,
+}
+
+/// A file that is not stored in the cartridge, because it has the same contents as another file that is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntryAlias {
+    /// Path of the file within the cartridge
+    pub path: String,
+    /// Path of the file (with the same contents) that is stored in the cartridge
+    pub target: String,
+}
+
+/// Raw reference to an asset within the cartridge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetDefinition {
+    pub id: Uuid,
+    #[serde(rename = "type")]
+    pub asset_type: AssetType,
+    /// Path of the asset within the cartridge
+    pub path: PathBuf,
+    /// For scripts that default-export a `ScriptComponent` class, metadata about that class
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub script_component: Option<ScriptComponentClassDefinition>,
+    /// IDs of other assets this asset refers to e.g. the materials used by a mesh, or the textures used by a material
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<Uuid>,
+}
+
+/// Metadata about a script's default-exported `ScriptComponent` class
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptComponentClassDefinition {
+    /// Name of the class. `None` if the class is anonymous.
+    pub class_name: Option<String>,
+    /// Lifecycle methods (e.g. `onUpdate`) that the class overrides
+    pub lifecycle_methods: Vec<String>,
+}
+
+/// Raw game scene definition. This is also the contents of a `.pzscene` file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneDefinition {
+    /// @NOTE In the editor, `path` comes from the scene's location on disk.
+    /// It is not stored as a property in the scene file.
+    #[serde(skip_deserializing)]
+    pub path: PathBuf,
+    pub config: SceneConfigDefinition,
+    pub objects: Vec<GameObjectDefinition>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneConfigDefinition {
+    pub clear_color: ColorDefinition,
+    pub lighting: SceneLightingDefinition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneLightingDefinition {
+    pub ambient: AmbientLightingDefinition,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmbientLightingDefinition {
+    pub intensity: f64,
+    pub color: ColorDefinition,
+}
+
+/// Raw game object data within a scene.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameObjectDefinition {
+    pub id: Uuid,
+    pub name: String,
+    pub transform: TransformDefinition,
+    pub components: Vec<ComponentDefinition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub children: Option<Vec<GameObjectDefinition>>,
+}
+
+impl GameObjectDefinition {
+    /// This object's children, if it has any
+    pub fn children(&self) -> &[GameObjectDefinition] {
+        self.children.as_deref().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformDefinition {
+    pub position: Vector3Definition,
+    pub rotation: Vector3Definition,
+    pub scale: Vector3Definition,
+}
+
+/// Raw game object component data.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ComponentDefinition {
+    #[serde(rename = "mesh", rename_all = "camelCase")]
+    Mesh {
+        id: Uuid,
+        // @TODO Rename to `meshAssetId`
+        mesh_file_id: Option<Uuid>,
+    },
+    #[serde(rename = "script", rename_all = "camelCase")]
+    Script {
+        id: Uuid,
+        // @TODO Rename to `scriptAssetId`
+        #[serde(default)]
+        script_file_id: Option<Uuid>,
+    },
+    #[serde(rename = "camera", rename_all = "camelCase")]
+    Camera {
+        id: Uuid,
+    },
+    #[serde(rename = "light_directional", rename_all = "camelCase")]
+    DirectionalLight {
+        id: Uuid,
+        intensity: f64,
+        color: ColorDefinition,
+    },
+    #[serde(rename = "light_point", rename_all = "camelCase")]
+    PointLight {
+        id: Uuid,
+        intensity: f64,
+        color: ColorDefinition,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorDefinition {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Vector3Definition {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
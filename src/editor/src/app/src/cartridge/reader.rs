@@ -0,0 +1,283 @@
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+use zip::ZipArchive;
+
+use crate::filesystem::assets::AssetType;
+use super::header::{CartridgeHeader, CARTRIDGE_FORMAT_VERSION};
+use super::manifest::{CartridgeArchiveManifest, ComponentDefinition, GameObjectDefinition};
+use super::{CARTRIDGE_HEADER_FILENAME, CARTRIDGE_MANIFEST_FILENAME};
+
+// Constants
+/// Most memory (in bytes) reserved up front when reading a file from a cartridge.
+/// Larger files still grow their buffer as they are read.
+const MAX_PREALLOCATED_FILE_SIZE: u64 = 16 * 1024 * 1024;
+
+// Types
+/// Reads the contents of a cartridge (zip archive) written by the editor.
+/// The manifest (and header, if the cartridge has one) are parsed when the cartridge is opened.
+pub struct CartridgeReader<R: Read + Seek> {
+    archive: ZipArchive<R>,
+    header: Option<CartridgeHeader>,
+    manifest: CartridgeArchiveManifest,
+}
+
+/// A single file within a cartridge
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartridgeEntry {
+    /// Path of the file within the cartridge
+    pub path: String,
+    /// Size (in bytes) of the file, uncompressed
+    pub size: u64,
+    /// Size (in bytes) of the file as stored in the cartridge
+    pub compressed_size: u64,
+    /// e.g. `Deflated`, `Stored`
+    pub compression_method: String,
+    /// `compressed_size` as a fraction of `size` (i.e. lower is better). 1 for empty files.
+    pub compression_ratio: f64,
+}
+
+/// Everything known about a cartridge without loading it, returned by the `inspect_cartridge` command
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CartridgeInspection {
+    /// `None` for cartridges built before headers were written
+    pub header: Option<CartridgeHeader>,
+    pub manifest: CartridgeArchiveManifest,
+    pub entries: Vec<CartridgeEntry>,
+    /// Problems with the manifest that would stop the cartridge from loading (see [`CartridgeReader::validate`])
+    pub problems: Vec<String>,
+}
+
+impl CartridgeReader<BufReader<File>> {
+    /// Open a cartridge file on disk
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = File::open(path).map_err(|error| format!("Failed to open cartridge: {error}"))?;
+        Self::new(BufReader::new(file))
+    }
+}
+
+impl<R: Read + Seek> CartridgeReader<R> {
+    /// Read a cartridge from `reader`. Fails if it is not a zip archive, or its manifest (or header) cannot be parsed.
+    pub fn new(reader: R) -> Result<Self, String> {
+        let mut archive = ZipArchive::new(reader).map_err(|error| format!("Failed to read cartridge: {error}"))?;
+
+        let manifest_file_bytes = read_archive_file(&mut archive, CARTRIDGE_MANIFEST_FILENAME)?
+            .ok_or_else(|| format!("Cartridge has no {CARTRIDGE_MANIFEST_FILENAME}"))?;
+        let manifest = parse_manifest(&manifest_file_bytes)
+            .map_err(|error| format!("Failed to parse {CARTRIDGE_MANIFEST_FILENAME}: {error}"))?;
+
+        let header = read_archive_file(&mut archive, CARTRIDGE_HEADER_FILENAME)?
+            .map(|header_file_bytes| serde_json::from_slice(&header_file_bytes))
+            .transpose()
+            .map_err(|error| format!("Failed to parse {CARTRIDGE_HEADER_FILENAME}: {error}"))?;
+
+        Ok(Self {
+            archive,
+            header,
+            manifest,
+        })
+    }
+
+    /// The cartridge's header. `None` for cartridges built before headers were written.
+    pub fn header(&self) -> Option<&CartridgeHeader> {
+        self.header.as_ref()
+    }
+
+    pub fn manifest(&self) -> &CartridgeArchiveManifest {
+        &self.manifest
+    }
+
+    /// Every file in the cartridge, in the order they are stored
+    pub fn entries(&mut self) -> Vec<CartridgeEntry> {
+        let mut entries = Vec::new();
+        for index in 0..self.archive.len() {
+            let Ok(file) = self.archive.by_index_raw(index) else {
+                continue;
+            };
+            if file.is_dir() {
+                continue;
+            }
+            entries.push(CartridgeEntry {
+                path: file.name().to_string(),
+                size: file.size(),
+                compressed_size: file.compressed_size(),
+                compression_method: file.compression().to_string(),
+                compression_ratio: match file.size() {
+                    0 => 1.0,
+                    size => file.compressed_size() as f64 / size as f64,
+                },
+            });
+        }
+        entries
+    }
+
+    /// Read the (uncompressed) contents of a file in the cartridge. Files that were deduplicated
+    /// (see [`CartridgeArchiveManifest::aliases`]) are read from the file they are an alias of.
+    pub fn read_file(&mut self, path: &str) -> Result<Vec<u8>, String> {
+        let archive_path = self.manifest.aliases.iter()
+            .find(|alias| alias.path == path)
+            .map_or(path, |alias| alias.target.as_str());
+        read_archive_file(&mut self.archive, archive_path)?
+            .ok_or_else(|| format!("Cartridge has no file: {path}"))
+    }
+
+    /// Extract every file in the cartridge into `output_directory`, keeping their paths within the cartridge.
+    /// Files that were deduplicated are extracted at every path they were deduplicated from.
+    pub fn extract_all(&mut self, output_directory: &Path) -> Result<(), String> {
+        // @NOTE `extract` ignores any paths that would end up outside `output_directory`
+        self.archive.extract(output_directory)
+            .map_err(|error| format!("Failed to extract cartridge: {error}"))?;
+
+        for alias in self.manifest.aliases.iter() {
+            let (Some(path), Some(target)) = (safe_relative_path(&alias.path), safe_relative_path(&alias.target)) else {
+                return Err(format!("Cannot extract file with invalid path: {}", alias.path));
+            };
+            let path = output_directory.join(path);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(|error| format!("Failed to extract {}: {error}", alias.path))?;
+            }
+            std::fs::copy(output_directory.join(target), &path)
+                .map_err(|error| format!("Failed to extract {}: {error}", alias.path))?;
+        }
+        Ok(())
+    }
+
+    /// Check that everything the manifest refers to actually exists, returning a description of each problem.
+    /// An empty list means the cartridge should load.
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        match &self.header {
+            Some(header) if header.format_version > CARTRIDGE_FORMAT_VERSION => problems.push(format!(
+                "Cartridge format version {} is newer than this editor supports ({})",
+                header.format_version,
+                CARTRIDGE_FORMAT_VERSION,
+            )),
+            Some(_) => {},
+            None => problems.push(format!("Cartridge has no {CARTRIDGE_HEADER_FILENAME}")),
+        }
+
+        // Assets
+        let mut archive_paths: HashSet<&str> = self.archive.file_names().collect();
+        for alias in self.manifest.aliases.iter() {
+            if archive_paths.contains(alias.target.as_str()) {
+                archive_paths.insert(alias.path.as_str());
+            } else {
+                problems.push(format!("File '{}' is an alias of a file that is not in the cartridge: {}", alias.path, alias.target));
+            }
+        }
+        let mut asset_types = HashMap::<Uuid, AssetType>::new();
+        for asset in self.manifest.assets.iter() {
+            if asset_types.insert(asset.id, asset.asset_type).is_some() {
+                problems.push(format!("Asset ID '{}' is used by more than one asset", asset.id));
+            }
+            // @NOTE Paths within a zip archive always use `/`
+            let path = asset.path.to_string_lossy().replace('\\', "/");
+            if !archive_paths.contains(path.as_str()) {
+                problems.push(format!("Asset '{}' refers to a file that is not in the cartridge: {}", asset.id, path));
+            }
+        }
+        for asset in self.manifest.assets.iter() {
+            for dependency_id in asset.dependencies.iter().filter(|dependency_id| !asset_types.contains_key(dependency_id)) {
+                problems.push(format!("Asset '{}' depends on asset '{}', which is not in the cartridge", asset.id, dependency_id));
+            }
+        }
+
+        for vendor_module in self.manifest.vendor_modules.iter().filter(|vendor_module| !archive_paths.contains(vendor_module.as_str())) {
+            problems.push(format!("Vendor module '{vendor_module}' is not in the cartridge"));
+        }
+
+        // Scenes
+        let scene_paths: HashSet<&Path> = self.manifest.scenes.iter().map(|scene| scene.path.as_path()).collect();
+        if let Some(entry_scene) = self.manifest.entry_scene.as_deref().filter(|entry_scene| !scene_paths.contains(entry_scene)) {
+            problems.push(format!("Entry scene '{}' is not in the cartridge", entry_scene.display()));
+        }
+        let mut scene_names = HashSet::new();
+        for named_scene in self.manifest.scene_list.iter() {
+            if !scene_names.insert(named_scene.name.as_str()) {
+                problems.push(format!("Scene name '{}' is used more than once", named_scene.name));
+            }
+            if !scene_paths.contains(named_scene.path.as_path()) {
+                problems.push(format!("Scene '{}' refers to a scene that is not in the cartridge: {}", named_scene.name, named_scene.path.display()));
+            }
+        }
+        for scene in self.manifest.scenes.iter() {
+            let mut objects: Vec<&GameObjectDefinition> = scene.objects.iter().collect();
+            while let Some(object) = objects.pop() {
+                objects.extend(object.children());
+                for component in object.components.iter() {
+                    let (asset_id, expected_type) = match component {
+                        ComponentDefinition::Mesh { mesh_file_id: Some(asset_id), .. } => (asset_id, AssetType::Mesh),
+                        ComponentDefinition::Script { script_file_id: Some(asset_id), .. } => (asset_id, AssetType::Script),
+                        _ => continue,
+                    };
+                    match asset_types.get(asset_id) {
+                        Some(asset_type) if *asset_type == expected_type => {},
+                        Some(asset_type) => problems.push(format!(
+                            "Object '{}' in scene '{}' refers to asset '{}', which is a {:?} asset (expected {:?})",
+                            object.name, scene.path.display(), asset_id, asset_type, expected_type,
+                        )),
+                        None => problems.push(format!(
+                            "Object '{}' in scene '{}' refers to asset '{}', which is not in the cartridge",
+                            object.name, scene.path.display(), asset_id,
+                        )),
+                    }
+                }
+            }
+        }
+
+        problems
+    }
+
+    /// Summarise the cartridge's header, manifest and files
+    pub fn inspect(&mut self) -> CartridgeInspection {
+        CartridgeInspection {
+            header: self.header.clone(),
+            manifest: self.manifest.clone(),
+            entries: self.entries(),
+            problems: self.validate(),
+        }
+    }
+}
+
+/// Parse the contents of a cartridge's `manifest.json`
+fn parse_manifest(manifest_file_bytes: &[u8]) -> Result<CartridgeArchiveManifest, serde_json::Error> {
+    let manifest_json: serde_json::Value = serde_json::from_slice(manifest_file_bytes)?;
+    let mut manifest: CartridgeArchiveManifest = serde_json::from_value(manifest_json.clone())?;
+
+    // @NOTE Scene paths are not deserialized (scene files on disk do not contain them), but they are in the manifest
+    for (index, scene) in manifest.scenes.iter_mut().enumerate() {
+        if let Some(path) = manifest_json["scenes"][index]["path"].as_str() {
+            scene.path = PathBuf::from(path);
+        }
+    }
+
+    Ok(manifest)
+}
+
+/// A path within the cartridge as a relative path, or `None` if it could point outside of the folder it is extracted to
+fn safe_relative_path(path: &str) -> Option<&Path> {
+    let path = Path::new(path);
+    path.components()
+        .all(|component| matches!(component, std::path::Component::Normal(_)))
+        .then_some(path)
+}
+
+/// Read the (uncompressed) contents of a file in a zip archive. Returns `None` if there is no such file.
+pub(crate) fn read_archive_file<R: Read + Seek>(archive: &mut ZipArchive<R>, path: &str) -> Result<Option<Vec<u8>>, String> {
+    let file = match archive.by_name(path) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(error) => return Err(format!("Failed to read {path}: {error}")),
+    };
+    // @NOTE The size comes from the archive, which cannot be trusted, so it only bounds how much is read
+    let size = file.size();
+    let mut data = Vec::with_capacity(size.min(MAX_PREALLOCATED_FILE_SIZE) as usize);
+    file.take(size).read_to_end(&mut data).map_err(|error| format!("Failed to read {path}: {error}"))?;
+    Ok(Some(data))
+}
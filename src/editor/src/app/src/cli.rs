@@ -0,0 +1,285 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use tokio_util::sync::CancellationToken;
+
+use polyzone_editor_lib::build::diagnostics::BuildError;
+use polyzone_editor_lib::build::{build_project, BuildOptions, BuildProfile, BuildProgress};
+use polyzone_editor_lib::cartridge::integrity::{self, SignatureStatus};
+use polyzone_editor_lib::cartridge::reader::CartridgeReader;
+
+/// Headless PolyZone tooling. Does not start the editor.
+#[derive(Parser)]
+#[command(name = "polyzone_cli", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build a project into a cartridge
+    Build {
+        /// Path to the project's `.pzproj` file
+        project_file: PathBuf,
+        /// Path to write the cartridge to. Defaults to the project file path with a `.pzcart` extension
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        /// Kind of cartridge to build
+        #[arg(short, long, value_enum, default_value_t = CliBuildProfile::Release)]
+        profile: CliBuildProfile,
+        /// Store the original source of each script in its source map (debug builds only)
+        #[arg(long)]
+        embed_script_sources: bool,
+        /// Sign the cartridge with the key in this file (see `generate-signing-key`)
+        #[arg(long)]
+        signing_key: Option<PathBuf>,
+        /// Record this time (in seconds since the Unix epoch) as when the cartridge was built, so that
+        /// rebuilding the same project produces an identical cartridge. Defaults to `SOURCE_DATE_EPOCH`, if set.
+        #[arg(long)]
+        build_timestamp: Option<u64>,
+        /// Build one of the variants defined in the project file (e.g. a demo), instead of the whole project
+        #[arg(long)]
+        variant: Option<String>,
+    },
+    /// Check that a cartridge's contents match the hashes in its manifest, and that its signature (if any) is valid
+    Verify {
+        /// Path to the `.pzcart` file
+        cartridge: PathBuf,
+        /// Hex-encoded public key the cartridge must be signed with
+        #[arg(long)]
+        public_key: Option<String>,
+    },
+    /// Print a cartridge's header, the files it contains, and any problems with its manifest
+    Inspect {
+        /// Path to the `.pzcart` file
+        cartridge: PathBuf,
+        /// Print the full manifest as JSON
+        #[arg(long)]
+        manifest: bool,
+    },
+    /// Extract every file in a cartridge into a folder
+    Extract {
+        /// Path to the `.pzcart` file
+        cartridge: PathBuf,
+        /// Folder to extract files into
+        output: PathBuf,
+    },
+    /// Generate a new key for signing cartridges, and print its public key
+    GenerateSigningKey {
+        /// Path to write the key to. Keep this file private.
+        path: PathBuf,
+    },
+}
+
+/// Which kind of cartridge to build (see [`BuildProfile`])
+#[derive(Clone, Copy, ValueEnum)]
+enum CliBuildProfile {
+    /// Scripts are compiled with source maps
+    Debug,
+    /// For distributing the game. Scripts are minified
+    Release,
+}
+
+impl From<CliBuildProfile> for BuildProfile {
+    fn from(profile: CliBuildProfile) -> Self {
+        match profile {
+            CliBuildProfile::Debug => BuildProfile::Debug,
+            CliBuildProfile::Release => BuildProfile::Release,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match cli.command {
+        Command::Build { project_file, output, profile, embed_script_sources, signing_key, build_timestamp, variant } => {
+            let output = output.unwrap_or_else(|| project_file.with_extension("pzcart"));
+            let options = BuildOptions {
+                profile: profile.into(),
+                embed_script_sources,
+                signing_key_path: signing_key,
+                build_timestamp,
+                variant,
+                ..Default::default()
+            };
+            build_command(&project_file, &output, &options)
+        }
+        Command::Verify { cartridge, public_key } => verify_command(&cartridge, public_key.as_deref()),
+        Command::Inspect { cartridge, manifest } => inspect_command(&cartridge, manifest),
+        Command::Extract { cartridge, output } => {
+            match CartridgeReader::open(&cartridge).and_then(|mut cartridge| cartridge.extract_all(&output)) {
+                Ok(()) => {
+                    println!("Extracted cartridge to: {}", output.display());
+                    ExitCode::SUCCESS
+                }
+                Err(error) => {
+                    eprintln!("{error}");
+                    ExitCode::FAILURE
+                }
+            }
+        }
+        Command::GenerateSigningKey { path } => match integrity::generate_signing_key(&path) {
+            Ok(signing_key) => {
+                println!("Wrote signing key: {}", path.display());
+                println!("Public key: {}", integrity::get_public_key(&signing_key));
+                ExitCode::SUCCESS
+            }
+            Err(error) => {
+                eprintln!("{error}");
+                ExitCode::FAILURE
+            }
+        },
+    }
+}
+
+/// Read a project from disk, build it, and write the resulting cartridge to `output`
+fn build_command(project_file: &Path, output: &Path, options: &BuildOptions) -> ExitCode {
+    let project_root = match project_file.parent() {
+        // @NOTE Project file given as a bare file name i.e. it is in the current directory
+        Some(parent) if parent.as_os_str().is_empty() => PathBuf::from("."),
+        Some(parent) => parent.to_path_buf(),
+        // @NOTE if you create a project in the fs root, godspeed
+        None => PathBuf::new(),
+    };
+
+    let on_progress = |progress: BuildProgress| {
+        println!("[{}/{}] {}", progress.current, progress.total, progress.path);
+    };
+
+    // @NOTE The editor's (Tauri) runtime is not set up here, so the build gets its own
+    let runtime = match tokio::runtime::Builder::new_multi_thread().enable_all().build() {
+        Ok(runtime) => runtime,
+        Err(error) => {
+            eprintln!("Failed to start build runtime: {error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    // Compile cartridge
+    let result = runtime.block_on(build_project(
+        &project_root,
+        project_file,
+        None,
+        output,
+        options,
+        on_progress,
+        CancellationToken::new(),
+    ));
+
+    match result {
+        Ok(report) => {
+            println!("{report}");
+            println!("Wrote cartridge: {}", output.display());
+            ExitCode::SUCCESS
+        }
+        Err(BuildError::Failed { diagnostics }) => {
+            for diagnostic in diagnostics.iter() {
+                eprintln!("{diagnostic}");
+            }
+            eprintln!("Build failed with {} problem(s)", diagnostics.len());
+            ExitCode::FAILURE
+        }
+        Err(BuildError::Cancelled) => {
+            eprintln!("Build cancelled");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Check a cartridge against its manifest, and print every file that does not match
+fn verify_command(cartridge: &Path, public_key: Option<&str>) -> ExitCode {
+    let result = File::open(cartridge)
+        .map_err(|error| format!("Failed to open cartridge: {error}"))
+        .and_then(|file| integrity::verify_cartridge(BufReader::new(file), public_key));
+    let verification = match result {
+        Ok(verification) => verification,
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match &verification.signature {
+        SignatureStatus::Unsigned => println!("Signature: none"),
+        SignatureStatus::Valid { public_key } => println!("Signature: valid ({public_key})"),
+        SignatureStatus::Untrusted { public_key } => eprintln!("Signature: valid, but signed by an unexpected key ({public_key})"),
+        SignatureStatus::Invalid { reason } => eprintln!("Signature: invalid ({reason})"),
+    }
+    for failed_entry in verification.failed_entries.iter() {
+        eprintln!("{:?}: {}", failed_entry.problem, failed_entry.path);
+    }
+    println!("{} file(s) verified, {} failed", verification.verified_entry_count, verification.failed_entries.len());
+
+    match verification.is_valid() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
+
+/// Print a summary of a cartridge's contents
+fn inspect_command(cartridge: &Path, print_manifest: bool) -> ExitCode {
+    let inspection = match CartridgeReader::open(cartridge) {
+        Ok(mut cartridge) => cartridge.inspect(),
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if let Some(header) = &inspection.header {
+        match &header.game_version {
+            Some(game_version) => println!("{} {}", header.title, game_version),
+            None => println!("{}", header.title),
+        }
+        if let Some(author) = &header.author {
+            println!("By: {author}");
+        }
+        println!("Format version: {}, minimum engine version: {}, profile: {:?}", header.format_version, header.min_engine_version, header.build_profile);
+        if let Some(build_variant) = &header.build_variant {
+            println!("Variant: {build_variant}");
+        }
+    }
+    println!("{} scene(s), {} asset(s)", inspection.manifest.scenes.len(), inspection.manifest.assets.len());
+    if let Some(entry_scene) = &inspection.manifest.entry_scene {
+        println!("Entry scene: {}", entry_scene.display());
+    }
+    if !inspection.manifest.vendor_modules.is_empty() {
+        println!("{} vendor module(s) from npm packages", inspection.manifest.vendor_modules.len());
+    }
+
+    let mut total_size = 0;
+    let mut total_compressed_size = 0;
+    for entry in inspection.entries.iter() {
+        println!(
+            "{:>10} {:>10} {:>5.1}% {:<10} {}",
+            entry.size,
+            entry.compressed_size,
+            entry.compression_ratio * 100.0,
+            entry.compression_method,
+            entry.path,
+        );
+        total_size += entry.size;
+        total_compressed_size += entry.compressed_size;
+    }
+    println!("{:>10} {:>10} {} file(s)", total_size, total_compressed_size, inspection.entries.len());
+
+    if print_manifest {
+        match serde_json::to_string_pretty(&inspection.manifest) {
+            Ok(manifest) => println!("{manifest}"),
+            Err(error) => eprintln!("Failed to print manifest: {error}"),
+        }
+    }
+
+    for problem in inspection.problems.iter() {
+        eprintln!("Problem: {problem}");
+    }
+    match inspection.problems.is_empty() {
+        true => ExitCode::SUCCESS,
+        false => ExitCode::FAILURE,
+    }
+}
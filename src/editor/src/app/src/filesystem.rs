@@ -22,9 +22,11 @@ use twox_hash::XxHash3_64;
 /// Debounce time to deduplicate successive filesystem events before re-scanning
 const FS_EVENT_DEBOUNCE_DURATION: Duration = Duration::from_secs(1);
 /// Path globs that are hard-coded excludes i.e. don't rely on `.pzignore` file to be ignored
-const EXCLUDED_PATH_GLOBS: [&str; 4] = [
+const EXCLUDED_PATH_GLOBS: [&str; 5] = [
     "**/node_modules/",
     "**/.git/",
+    // Build cache
+    "**/.polyzone/",
     // Explicitly whitelist project & scene files
     "!**/.pzproj",
     "!**/.pzscene",
@@ -154,8 +156,8 @@ async fn watch_fs(state: Arc<FsWatcherState>, cancellation_token: CancellationTo
                                 // @NOTE is this too slow? We might receive a lot of events :/
                                 let project_definition = match read_project_definition(&state).await {
                                     Ok(project_definition) => project_definition,
-                                    Err(_) => {
-                                        log::warn!("[filesystem] (watch_fs) Failed while reading project definition, skipping event");
+                                    Err(error) => {
+                                        log::warn!("[filesystem] (watch_fs) Failed while reading project definition, skipping event: {}", error);
                                         continue;
                                     },
                                 };
@@ -364,6 +366,15 @@ pub async fn get_file_hash(path: &PathBuf) -> String {
     format!("{:x}", result)
 }
 
+/// Get the hash of some data as a string. Hash algorithm used is
+/// XXH3_64bits (i.e. the same as [`get_file_hash`]).
+pub fn get_data_hash(data: &[u8]) -> String {
+    let mut hasher = XxHash3_64::new();
+    hasher.write(data);
+    let result = hasher.finish();
+    format!("{:x}", result)
+}
+
 // @NOTE Only needed until this PR is merged: https://github.com/watchexec/watchexec/pull/908
 // From watchexec source: // https://github.com/watchexec/watchexec/blob/c0b01a43a39bfbc5e4aa5a19d5791326d30cb57e/crates/ignore-files/src/filter.rs#L410
 fn prefix<T: AsRef<std::path::Path>>(path: T) -> String {
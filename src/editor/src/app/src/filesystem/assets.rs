@@ -2,24 +2,54 @@ use serde::{Deserialize, Serialize};
 use ignore_files::IgnoreFilter;
 use tauri::Emitter;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::task::JoinSet;
 use tokio::time::Instant;
 use uuid::Uuid;
 use walkdir::WalkDir;
 use super::{get_file_hash, FsWatcherState};
+use crate::build::compiler::CompilerConfig;
+use crate::build::properties::{read_script_property_schema, ScriptPropertySchema};
 use crate::filesystem::project::read_project_definition;
 
 
 /// List of all file extensions that are supported asset types - Should be kept in-sync with the frontend business logic
 /// @TODO Send these to the frontend for a single source of truth
-const SUPPORTED_ASSET_FILE_TYPES: [&str; 17] = [
-    "obj", "fbx", "gltf", "glb", "stl", "mtl", "ts", "js", "mp3", "ogg", "wav", "png", "jpg",
+const SUPPORTED_ASSET_FILE_TYPES: [&str; 20] = [
+    "obj", "fbx", "gltf", "glb", "stl", "mtl", "bin", "ts", "tsx", "js", "jsx", "mp3", "ogg", "wav", "png", "jpg",
     "jpeg", "bmp", "basis", "dds",
 ];
 
 // Types
+/// Type of an asset, based on its file extension - Should be kept in-sync with `AssetTypeMap` in the runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AssetType {
+    Mesh,
+    MeshSupplementary,
+    Script,
+    Sound,
+    Texture,
+    Unknown,
+}
+
+impl AssetType {
+    /// Determine the type of an asset from its path
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path.extension().and_then(|extension| extension.to_str());
+        match extension {
+            Some("obj" | "fbx" | "gltf" | "glb" | "stl") => AssetType::Mesh,
+            // @NOTE `.bin` files are the external buffers of `.gltf` meshes
+            Some("mtl" | "bin") => AssetType::MeshSupplementary,
+            Some("ts" | "tsx" | "js" | "jsx") => AssetType::Script,
+            Some("mp3" | "ogg" | "wav") => AssetType::Sound,
+            Some("png" | "jpg" | "jpeg" | "bmp" | "basis" | "dds") => AssetType::Texture,
+            _ => AssetType::Unknown,
+        }
+    }
+}
+
 /// An asset file on disk
 pub struct AssetFile {
     pub path: PathBuf,
@@ -53,6 +83,14 @@ pub enum AssetFsEvent {
     Rename { asset_id: Uuid, new_path: PathBuf },
 }
 
+/// An event representing that the inspector property schema of a script has changed
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptPropertySchemaEvent {
+    pub asset_id: Uuid,
+    pub schema: ScriptPropertySchema,
+}
+
 
 /// Perform a reconciliation of what assets are known in memory vs.
 /// what assets exist on disk. Any differences will be emitted as changes.
@@ -83,6 +121,7 @@ pub async fn perform_asset_reconciliation(state: Arc<FsWatcherState>) {
 
     // Look through list of asset files on disk to find creates / modifications
     let mut new_asset_files = Vec::<&AssetFile>::new();
+    let mut modified_scripts = Vec::<&AssetDefinition>::new();
     for asset_file in all_asset_files.iter() {
         // See if any known assets have the same path as the file on disk
         let known_asset = unchecked_assets.get(&asset_file.path);
@@ -101,6 +140,9 @@ pub async fn perform_asset_reconciliation(state: Arc<FsWatcherState>) {
                         asset_id: known_asset.id,
                         new_hash: asset_file.hash.clone(),
                     });
+                    if AssetType::from_path(&known_asset.path) == AssetType::Script {
+                        modified_scripts.push(known_asset);
+                    }
                 }
 
                 // Since we've matched a file on disk with a known asset, we
@@ -157,7 +199,14 @@ pub async fn perform_asset_reconciliation(state: Arc<FsWatcherState>) {
     );
 
     if fs_events.len() > 0 {
-        on_asset_fs_event(fs_events, state).await;
+        on_asset_fs_event(fs_events, state.clone()).await;
+    }
+
+    if !modified_scripts.is_empty() {
+        let modified_scripts = modified_scripts.into_iter()
+            .map(|asset| (asset.id, asset.path.clone()))
+            .collect();
+        on_scripts_modified(modified_scripts, state).await;
     }
 }
 
@@ -189,6 +238,49 @@ async fn on_asset_fs_event(events: Vec<AssetFsEvent>, state: Arc<FsWatcherState>
     }
 }
 
+/// Callback for when script assets have been modified. Re-reads the inspector property schema of each script.
+async fn on_scripts_modified(scripts: Vec<(Uuid, PathBuf)>, state: Arc<FsWatcherState>) {
+    let project_root = state.project_root.clone();
+    // @NOTE Scripts are parsed with the project's compiler options (e.g. whether JSX is allowed)
+    let compiler = match read_project_definition(&state).await {
+        Ok(project_definition) => project_definition.compiler,
+        Err(error) => {
+            log::warn!("[on_scripts_modified] Failed to read project file. Using default compiler options: {:?}", error);
+            CompilerConfig::default()
+        }
+    };
+    let schema_events = tauri::async_runtime::spawn_blocking(move || {
+        scripts.into_iter()
+            .filter_map(|(asset_id, path)| match read_script_property_schema(&project_root, &path, &compiler) {
+                Ok(schema) => Some(ScriptPropertySchemaEvent { asset_id, schema }),
+                Err(error) => {
+                    // @NOTE Most likely the script is in the middle of being edited. It will be re-read once it is fixed.
+                    log::debug!("[on_scripts_modified] Failed to read property schema of script {:?}: {}", path, error.message);
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    }).await;
+
+    let schema_events = match schema_events {
+        Ok(schema_events) => schema_events,
+        Err(error) => {
+            log::error!("[on_scripts_modified] Failed to read script property schemas: {:?}", error);
+            return;
+        }
+    };
+    if schema_events.is_empty() {
+        return;
+    }
+
+    // Emit data to JavaScript
+    const EVENT_NAME: &str = "on_script_property_schemas_updated";
+    match state.app.emit(EVENT_NAME, schema_events) {
+        Ok(_) => log::debug!("[on_scripts_modified] Emitted event `{EVENT_NAME}`"),
+        Err(error) => log::error!("[on_scripts_modified] Error emitting event `{EVENT_NAME}`: {error:?}"),
+    }
+}
+
 /// Find all project asset files on disk
 async fn get_all_asset_files(project_root: &PathBuf, ignore_filter: &IgnoreFilter) -> Vec<AssetFile> {
     let walker = WalkDir::new(project_root).into_iter();
@@ -1,14 +1,19 @@
 use serde::{Deserialize, Serialize};
 use ignore_files::IgnoreFilter;
 use tauri::Emitter;
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::task::JoinSet;
 use tokio::time::Instant;
 use walkdir::WalkDir;
+use crate::build::compiler::CompilerConfig;
+use crate::build::compression::CompressionPolicy;
+use crate::build::variants::BuildVariant;
 use super::{assets::AssetDefinition, scenes::SceneDefinition, get_file_hash, FsWatcherState};
 use tokio::fs::File;
 use tokio::io::AsyncReadExt;
+use uuid::Uuid;
 
 // Constants
 const PROJECT_FILE_EXTENSION: &str = "pzproj";
@@ -21,10 +26,18 @@ pub struct ProjectFile {
     pub hash: String,
 }
 
+/// Metadata about the project (i.e. the game), written into the header of every cartridge
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ProjectManifest {
-    project_name: String,
+    pub project_name: String,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// Version of the game e.g. `1.2.0`
+    pub version: Option<String>,
+    /// Oldest version of the engine that can play the game e.g. `0.1.0`.
+    /// Defaults to the version of the engine the editor builds cartridges for.
+    pub min_engine_version: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -33,6 +46,39 @@ pub struct ProjectDefinition {
     pub manifest: ProjectManifest,
     pub assets: Vec<AssetDefinition>,
     pub scenes: Vec<SceneDefinition>,
+    /// How each kind of file is compressed in cartridges built from this project
+    #[serde(default)]
+    pub compression: CompressionPolicy,
+    /// How scripts are compiled e.g. which version of JavaScript they target
+    #[serde(default)]
+    pub compiler: CompilerConfig,
+    /// Assets to put in every cartridge, even if no scene uses them (e.g. because they are loaded dynamically).
+    /// Each entry is an asset ID, the path of an asset, a folder (ending in `/`) of assets, or a glob (e.g. `textures/**/*.png`).
+    #[serde(default)]
+    pub include_assets: Vec<String>,
+    /// ID of the scene the game starts in. Defaults to the first scene in `scene_list`, or else the first scene in `scenes`.
+    #[serde(default)]
+    pub entry_scene: Option<Uuid>,
+    /// Logical names for the project's scenes, in order, which the game can use to switch between them.
+    /// Scenes are written into cartridges in this order (after the entry scene), followed by any scenes that are not listed.
+    #[serde(default)]
+    pub scene_list: Vec<NamedSceneReference>,
+    /// Named subsets of the project that can be built instead of the whole project e.g. a demo
+    #[serde(default)]
+    pub variants: HashMap<String, BuildVariant>,
+    /// Global identifiers in scripts that are replaced with a value (a boolean, number, string or null) when they
+    /// are compiled, e.g. `{ "__LEVEL_COUNT__": 3 }`. See [`crate::build::defines`].
+    #[serde(default)]
+    pub defines: HashMap<String, serde_json::Value>,
+}
+
+/// A logical name for one of the project's scenes
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedSceneReference {
+    pub name: String,
+    /// ID of the scene (see [`ProjectDefinition::scenes`])
+    pub id: Uuid,
 }
 
 /// An event representing a change to a project file
@@ -233,21 +279,46 @@ pub fn is_path_project_file(path: &PathBuf) -> bool {
     }
 }
 
-pub async fn read_project_definition(state: &Arc<FsWatcherState>) -> Result<ProjectDefinition, &str> {
+/// Find the project file in the root of a project directory
+pub fn find_project_file(project_root: &Path) -> Result<PathBuf, &'static str> {
+    let entries = std::fs::read_dir(project_root).map_err(|_| "Failed to read project directory")?;
+
+    let mut project_files = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && is_path_project_file(path));
+
+    match (project_files.next(), project_files.next()) {
+        (Some(project_file_path), None) => Ok(project_file_path),
+        (None, _) => Err("No project file found in project directory"),
+        (Some(_), Some(_)) => Err("Found multiple project files in project directory"),
+    }
+}
+
+pub async fn read_project_definition(state: &Arc<FsWatcherState>) -> Result<ProjectDefinition, String> {
     let project_file_path = state.project_file_absolute_path().await;
-    let mut file = File::open(&project_file_path).await.map_err(|_| "Failed to open project file")?;
+    read_project_definition_from_path(&project_file_path).await
+}
+
+/// Read and parse the project definition from a project file on disk.
+/// Does not depend on any watcher state, so it can be used outside of the editor (e.g. from the CLI).
+/// Errors include the path of the project file and the reason it could not be read (e.g. a misspelled option).
+pub async fn read_project_definition_from_path(project_file_path: &Path) -> Result<ProjectDefinition, String> {
+    let path = project_file_path.display();
+    let mut file = File::open(project_file_path).await
+        .map_err(|error| format!("Failed to open project file {path}: {error}"))?;
     let mut jsonc = String::new();
-    file.read_to_string(&mut jsonc).await.map_err(|_| "Failed to read project file contents")?;
+    file.read_to_string(&mut jsonc).await
+        .map_err(|error| format!("Failed to read project file {path}: {error}"))?;
 
-    let parsed_jsonc = jsonc_parser::parse_to_serde_value(&jsonc, &Default::default()).map_err(|_| "Failed to parse project file JSONC")?;
+    let parsed_jsonc = jsonc_parser::parse_to_serde_value(&jsonc, &Default::default())
+        .map_err(|error| format!("Failed to parse project file {path}: {error}"))?;
     match parsed_jsonc {
-        Some(parse_result) => {
-            let project_definition: ProjectDefinition = serde_json::from_value(parse_result).expect("Failed to convert JSONC into ProjectDefinition");
-            Ok(project_definition)
-        },
+        Some(parse_result) => serde_json::from_value(parse_result)
+            .map_err(|error| format!("Failed to read project definition from {path}: {error}")),
         None => {
             log::error!("Failed to parse project JSONC: {:?}", jsonc);
-            Err("Failed to parse project JSONC")
+            Err(format!("Failed to parse project file {path}: the file is empty"))
         },
     }
 }
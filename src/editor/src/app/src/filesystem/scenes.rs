@@ -2,12 +2,15 @@ use serde::{Deserialize, Serialize};
 use ignore_files::IgnoreFilter;
 use tauri::Emitter;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::task::JoinSet;
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
 use tokio::time::Instant;
 use uuid::Uuid;
 use walkdir::WalkDir;
+use crate::cartridge;
 use crate::filesystem::project::read_project_definition;
 
 use super::{get_file_hash, FsWatcherState};
@@ -255,3 +258,23 @@ pub fn is_path_scene_file(path: &PathBuf) -> bool {
         None => false,
     }
 }
+
+/// Read and parse a scene file from disk.
+/// Errors include the path of the scene file and the reason it could not be read.
+/// @NOTE The returned scene definition's `path` is not set, as it is not stored in the scene file.
+pub async fn read_scene_definition_from_path(scene_file_path: &Path) -> Result<cartridge::manifest::SceneDefinition, String> {
+    let path = scene_file_path.display();
+    let mut file = File::open(scene_file_path).await
+        .map_err(|error| format!("Failed to open scene file {path}: {error}"))?;
+    let mut jsonc = String::new();
+    file.read_to_string(&mut jsonc).await
+        .map_err(|error| format!("Failed to read scene file {path}: {error}"))?;
+
+    let parsed_jsonc = jsonc_parser::parse_to_serde_value(&jsonc, &Default::default())
+        .map_err(|error| format!("Failed to parse scene file {path}: {error}"))?;
+    match parsed_jsonc {
+        Some(parse_result) => serde_json::from_value(parse_result)
+            .map_err(|error| format!("Failed to read scene definition from {path}: {error}")),
+        None => Err(format!("Failed to parse scene file {path}: the file is empty")),
+    }
+}
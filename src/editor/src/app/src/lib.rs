@@ -1,15 +1,22 @@
-mod build;
-mod filesystem;
+pub mod build;
+pub mod cartridge;
+pub mod filesystem;
 mod polyzone;
 
 use std::hash::Hasher as _;
-use std::path::PathBuf;
-
-use build::build;
+use std::path::{Path, PathBuf};
+
+use build::{build_project, BuildOptions, BuildProgress, BuildReport};
+use build::diagnostics::{BuildDiagnostic, BuildError};
+use build::properties::{read_script_property_schema, ScriptPropertySchema};
+use cartridge::integrity::CartridgeVerification;
+use cartridge::reader::{CartridgeInspection, CartridgeReader};
+use filesystem::project::{find_project_file, read_project_definition_from_path};
 use polyzone::PolyZoneApp;
-use tauri::Manager;
+use tauri::{AppHandle, Emitter, Manager};
 use tauri::async_runtime::Mutex;
 use twox_hash::XxHash3_64;
+use uuid::Uuid;
 
 type PolyZoneAppState<'a> = tauri::State<'a, Mutex<PolyZoneApp>>;
 
@@ -35,32 +42,60 @@ pub fn run() {
         )
         .invoke_handler(tauri::generate_handler![
             create_cartridge,
+            cancel_create_cartridge,
             load_project,
             unload_project,
             start_watching_project_files,
             stop_watching_project_assets,
             hash_data,
             notify_project_file_updated,
+            get_script_property_schema,
+            verify_cartridge,
+            inspect_cartridge,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
 
 #[tauri::command]
-fn create_cartridge(
-    manifest_file_bytes: &str,
+async fn create_cartridge(
+    app: AppHandle,
+    poly_zone_app: PolyZoneAppState<'_>,
     project_root_path: &str,
-    asset_paths: Vec<&str>,
-    script_paths: Vec<&str>,
-) -> Vec<u8> {
-    let cartridge_bytes = build(
-        manifest_file_bytes.as_bytes(),
-        project_root_path,
-        asset_paths,
-        script_paths,
-    );
-
-    cartridge_bytes
+    entry_scene_id: Option<Uuid>,
+    output_path: &str,
+    options: Option<BuildOptions>,
+) -> Result<BuildReport, BuildError> {
+    let project_root = Path::new(project_root_path);
+    let project_file_path = find_project_file(project_root)
+        .map_err(|error| BuildError::single(BuildDiagnostic::error("", error)))?;
+    let cancellation_token = poly_zone_app.lock().await.start_build();
+
+    // Report progress back to JavaScript
+    let on_progress = move |progress: BuildProgress| {
+        const EVENT_NAME: &str = "build_progress";
+        if let Err(error) = app.emit(EVENT_NAME, progress) {
+            log::error!("[create_cartridge] Error emitting event `{EVENT_NAME}`: {error:?}");
+        }
+    };
+
+    build_project(
+        project_root,
+        &project_file_path,
+        entry_scene_id,
+        Path::new(output_path),
+        &options.unwrap_or_default(),
+        on_progress,
+        cancellation_token,
+    ).await
+}
+
+#[tauri::command]
+async fn cancel_create_cartridge(poly_zone_app: PolyZoneAppState<'_>) -> Result<(), ()> {
+    let mut poly_zone_app = poly_zone_app.lock().await;
+    poly_zone_app.cancel_build();
+
+    Ok(())
 }
 
 #[tauri::command]
@@ -120,3 +155,50 @@ async fn notify_project_file_updated(
     poly_zone_app.notify_project_file_updated(data).await;
     Ok(())
 }
+
+#[tauri::command]
+async fn get_script_property_schema(
+    poly_zone_app: PolyZoneAppState<'_>,
+    script_path: &str,
+) -> Result<ScriptPropertySchema, BuildDiagnostic> {
+    let Some(project_root) = poly_zone_app.lock().await.project_root.clone() else {
+        return Err(BuildDiagnostic::error(script_path, "Cannot read script: No project is loaded"));
+    };
+
+    // @NOTE Scripts are parsed with the project's compiler options (e.g. whether JSX is allowed)
+    let project_file_path = find_project_file(&project_root)
+        .map_err(|error| BuildDiagnostic::error("", error))?;
+    let compiler = read_project_definition_from_path(&project_file_path).await
+        .map_err(|error| BuildDiagnostic::error("", error))?
+        .compiler;
+
+    let script_path = PathBuf::from(script_path);
+    tauri::async_runtime::spawn_blocking(move || read_script_property_schema(&project_root, &script_path, &compiler))
+        .await
+        .map_err(|error| BuildDiagnostic::error("", format!("Failed to read script: {error}")))?
+}
+
+#[tauri::command]
+async fn verify_cartridge(
+    cartridge_path: &str,
+    trusted_public_key: Option<String>,
+) -> Result<CartridgeVerification, String> {
+    let cartridge_path = PathBuf::from(cartridge_path);
+    tauri::async_runtime::spawn_blocking(move || {
+        let file = std::fs::File::open(&cartridge_path)
+            .map_err(|error| format!("Failed to open cartridge: {error}"))?;
+        cartridge::integrity::verify_cartridge(std::io::BufReader::new(file), trusted_public_key.as_deref())
+    })
+        .await
+        .map_err(|error| format!("Failed to verify cartridge: {error}"))?
+}
+
+#[tauri::command]
+async fn inspect_cartridge(cartridge_path: &str) -> Result<CartridgeInspection, String> {
+    let cartridge_path = PathBuf::from(cartridge_path);
+    tauri::async_runtime::spawn_blocking(move || {
+        CartridgeReader::open(&cartridge_path).map(|mut cartridge| cartridge.inspect())
+    })
+        .await
+        .map_err(|error| format!("Failed to inspect cartridge: {error}"))?
+}
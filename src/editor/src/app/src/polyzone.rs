@@ -13,6 +13,7 @@ pub struct PolyZoneApp {
     app: AppHandle,
     watch_assets_state: Option<Arc<FsWatcherState>>,
     watch_assets_cancellation_token: Option<CancellationToken>,
+    build_cancellation_token: Option<CancellationToken>,
 }
 
 unsafe impl Send for PolyZoneApp {}
@@ -27,6 +28,7 @@ impl PolyZoneApp {
             app,
             watch_assets_state: None,
             watch_assets_cancellation_token: None,
+            build_cancellation_token: None,
         }
     }
 
@@ -82,6 +84,23 @@ impl PolyZoneApp {
         }
     }
 
+    /// Begin tracking a new cartridge build. Any build that is already in progress is cancelled.
+    /// Returns a token that the build should observe for cancellation.
+    pub fn start_build(&mut self) -> CancellationToken {
+        self.cancel_build();
+
+        let cancellation_token = CancellationToken::new();
+        self.build_cancellation_token = Some(cancellation_token.clone());
+        cancellation_token
+    }
+
+    pub fn cancel_build(&mut self) {
+        if let Some(cancellation_token) = self.build_cancellation_token.take() {
+            log::debug!("Cancelling cartridge build");
+            cancellation_token.cancel();
+        }
+    }
+
     pub async fn notify_project_file_updated(&mut self, data: Vec<u8>) {
         let mut hasher = XxHash3_64::new();
         hasher.write(&data);
@@ -1,12 +1,11 @@
 import { makeAutoObservable, runInAction } from 'mobx';
 import { v4 as uuid } from 'uuid';
-
-import { AssetType, CartridgeArchiveManifest } from '@polyzone/runtime/src/cartridge';
+import { listen } from '@tauri-apps/api/event';
 
 import { ProjectController } from '@lib/project/ProjectController';
-import { toRuntimeSceneDefinition } from '@lib/project/definition';
 import { SceneData } from '@lib/project/data';
-import { invoke } from '@lib/util/TauriCommands';
+import { BuildError, BuildOptions, BuildProgress, invoke } from '@lib/util/TauriCommands';
+import { TauriEvents } from '@lib/util/TauriEvents';
 import { SceneViewController } from './scene/SceneViewController';
 
 
@@ -102,66 +101,53 @@ export class ComposerController {
     }
   }
 
-  // Kind of a debug method with a bit of a mashup of concerns
-  /*
-    @TODO
-    Is there a way we can do this from Rust, so that we
-    could do this from a CLI?
-  */
-  public async debug_buildCartridge(entryPointSceneIdOverride: string | undefined = undefined): Promise<Uint8Array> {
-
-    // Load scene definitions
-    const scenes = this.projectController.project.scenes.getAll();
-
-    // Move `overrideEntryPoint` to be the first scene in the list
-    if (entryPointSceneIdOverride !== undefined) {
-      const overrideIndex = scenes.findIndex((scene) => scene.manifest.id === entryPointSceneIdOverride);
-      if (overrideIndex === -1) {
-        throw new Error(`Cannot build cartridge. Cannot set entrypoint to SceneDefinition with ID '${entryPointSceneIdOverride}' - it isn't one of the current project's scenes`);
-      }
-
-      const override = scenes.splice(overrideIndex, 1)[0];
-      scenes.unshift(override);
-    }
-
-    // Build cartridge manifest
-    const manifest: CartridgeArchiveManifest = {
-      assets: this.projectController.project.assets.getAll()
-        .map((asset) => {
-          // @NOTE map assets to pluck only desired properties
-          if (asset.type === AssetType.Script) {
-            // @NOTE Scripts need to be renamed to .js
-            return {
-              id: asset.id,
-              type: asset.type,
-              path: asset.path.replace(/\.\w+$/, '.js'),
-            };
-          } else {
-            return {
-              id: asset.id,
-              type: asset.type,
-              path: asset.path,
-            };
-          }
-        }),
-      scenes: scenes.map((scene) =>
-        toRuntimeSceneDefinition(scene.jsonc.value, scene.manifest.path),
-      ),
-    };
+  // Kind of a debug method
+  public async debug_buildCartridge(
+    outputPath: string,
+    entryPointSceneIdOverride: string | undefined = undefined,
+    onProgress: ((progress: BuildProgress) => void) | undefined = undefined,
+    options: BuildOptions | undefined = undefined,
+  ): Promise<void> {
+    const stopListeningForProgress = await listen<BuildProgress>(TauriEvents.BuildProgress, (e) => onProgress?.(e.payload));
 
     // Compile cartridge file
-    const createCartridgeResult = await invoke('create_cartridge', {
-      manifestFileBytes: JSON.stringify(manifest),
-      projectRootPath: this.projectController.project.rootPath,
-      assetPaths: this.projectController.project.assets.getAll()
-        .filter((asset) => asset.type !== AssetType.Script)
-        .map((asset) => asset.path),
-      scriptPaths: this.projectController.project.assets.getAll()
-        .filter((asset) => asset.type === AssetType.Script)
-        .map((asset) => asset.path),
-    });
+    // @NOTE The cartridge is built from the project on disk, not from any state in the editor
+    try {
+      const report = await invoke('create_cartridge', {
+        projectRootPath: this.projectController.project.rootPath,
+        entrySceneId: entryPointSceneIdOverride,
+        outputPath,
+        options,
+      });
+      if (report.minifiedScriptSize !== null) {
+        console.log(`[ComposerController] (debug_buildCartridge) Minified scripts from ${report.scriptSize} bytes to ${report.minifiedScriptSize} bytes`);
+      } else {
+        console.log(`[ComposerController] (debug_buildCartridge) Scripts: ${report.scriptSize} bytes`);
+      }
+      if (report.duplicateFileCount > 0) {
+        console.log(`[ComposerController] (debug_buildCartridge) Stored ${report.duplicateFileCount} duplicate file(s) once, saving ${report.duplicateSize} bytes`);
+      }
+      if (report.excludedAssets.length > 0) {
+        console.log(`[ComposerController] (debug_buildCartridge) Excluded unused assets: `, report.excludedAssets);
+      }
+    } catch (e) {
+      const buildError = e as BuildError;
+      if (buildError.type === 'cancelled') {
+        throw new Error(`Cartridge build was cancelled`);
+      }
+      for (const diagnostic of buildError.diagnostics) {
+        const location = diagnostic.line !== null ? `:${diagnostic.line}:${diagnostic.column}` : '';
+        console.error(`[ComposerController] (debug_buildCartridge) ${diagnostic.path}${location} ${diagnostic.message}`, diagnostic.snippet ?? '');
+      }
+      throw new Error(`Failed to build cartridge. ${buildError.diagnostics.length} problem(s) found`);
+    } finally {
+      stopListeningForProgress();
+    }
+  }
 
-    return new Uint8Array(createCartridgeResult);
+  /** Cancel the cartridge build that is currently in progress (if any) */
+  public async debug_cancelBuildCartridge(): Promise<void> {
+    await invoke('cancel_create_cartridge');
   }
 
   public get currentlyOpenTabs(): TabData[] {
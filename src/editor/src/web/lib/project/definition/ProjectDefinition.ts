@@ -1,12 +1,126 @@
 import { AssetDefinition } from "./AssetDefinition";
 import { SceneManifest } from "./scene";
 
+/**
+ * Metadata about the project (i.e. the game), written into the header of every cartridge.
+ * @NOTE This must match `ProjectManifest` struct in: src/editor/src/app/src/filesystem/project.rs
+ */
 export interface ProjectManifest {
   readonly projectName: string;
+  readonly author?: string;
+  readonly description?: string;
+  /** Version of the game e.g. `1.2.0` */
+  readonly version?: string;
+  /** Oldest version of the engine that can play the game e.g. `0.1.0`. Defaults to the current engine version. */
+  readonly minEngineVersion?: string;
+}
+
+/**
+ * How a single category of files is compressed in a cartridge.
+ * - `deflate`: `level` is from 0 (fastest) to 9 (smallest)
+ * - `zstd`: `level` is from -7 (fastest) to 22 (smallest). Cannot be loaded by the web player (yet).
+ * @NOTE This must match `CompressionSetting` enum in: src/editor/src/app/src/build/compression.rs
+ */
+export type CompressionSetting =
+  { readonly method: 'stored' } |
+  { readonly method: 'deflate' | 'zstd'; readonly level?: number; };
+
+/**
+ * How each kind of file is compressed in cartridges built from the project.
+ * Any category not given uses its default.
+ * @NOTE This must match `CompressionPolicy` struct in: src/editor/src/app/src/build/compression.rs
+ */
+export interface CompressionPolicy {
+  /** Files in formats that are already compressed (e.g. `.png`, `.ogg`). Default: `stored` */
+  readonly precompressed?: CompressionSetting;
+  /** Meshes and material files. Default: `deflate` */
+  readonly meshes?: CompressionSetting;
+  /** Scripts, source maps and the cartridge manifest. Default: `deflate` */
+  readonly text?: CompressionSetting;
+  /** Any other files. Default: `deflate` */
+  readonly other?: CompressionSetting;
+}
+
+/**
+ * How scripts are compiled. Any option not given uses its default.
+ * @NOTE This must match `CompilerConfig` struct in: src/editor/src/app/src/build/compiler.rs
+ */
+export interface CompilerConfig {
+  /** Version of JavaScript that scripts are compiled to e.g. `es2022`. Must be `es2015` or newer. Default: `es2016` */
+  readonly target?: string;
+  /** Format of compiled modules. The web player can only load `amd` modules (for now). Default: `amd` */
+  readonly module?: 'amd';
+  /** Which proposal for decorators (if any) scripts are written in. Default: `2022-03` */
+  readonly decorators?: '2021-12' | '2022-03' | 'legacy' | 'none';
+  /** Allow JSX in `.tsx` (and `.js` / `.jsx`) scripts. Requires `jsxFactory`. Default: `false` */
+  readonly jsx?: boolean;
+  /** Function that JSX elements are compiled to calls of e.g. `h` */
+  readonly jsxFactory?: string;
+  /** Component that JSX fragments are compiled to e.g. `Fragment` */
+  readonly jsxFragmentFactory?: string;
 }
 
 export interface ProjectDefinition {
   readonly manifest: ProjectManifest,
   readonly assets: AssetDefinition[];
   readonly scenes: SceneManifest[];
+  readonly compression?: CompressionPolicy;
+  readonly compiler?: CompilerConfig;
+  /**
+   * Assets to put in every cartridge, even if no scene uses them (e.g. because they are loaded dynamically).
+   * Each entry is an asset ID, the path of an asset, a folder (ending in `/`) of assets, or a glob (e.g. `textures/**/*.png`).
+   */
+  readonly includeAssets?: string[];
+  /**
+   * ID of the scene the game starts in.
+   * Defaults to the first scene in `sceneList`, or else the first scene in `scenes`.
+   */
+  readonly entryScene?: string;
+  /**
+   * Logical names for the project's scenes, in order, which the game can use to switch between them.
+   */
+  readonly sceneList?: NamedSceneReference[];
+  /**
+   * Named subsets of the project that can be built instead of the whole project e.g. a demo.
+   */
+  readonly variants?: Record<string, BuildVariant>;
+  /**
+   * Global identifiers in scripts that are replaced with a value when they are compiled, e.g. `{ "__LEVEL_COUNT__": 3 }`.
+   * `__DEBUG__`, `__VARIANT__` and `__GAME_VERSION__` are always defined.
+   */
+  readonly defines?: Record<string, DefineValue>;
+}
+
+/**
+ * Value of a define. Substituted into scripts as a literal.
+ */
+export type DefineValue = boolean | number | string | null;
+
+/**
+ * A named subset of the project to build.
+ * @NOTE This must match `BuildVariant` struct in: src/editor/src/app/src/build/variants.rs
+ */
+export interface BuildVariant {
+  /** Scenes to include, each given by its ID or its name in `sceneList`. Every scene is included if not given. */
+  readonly scenes?: string[];
+  /** Assets to include as well as those in the project's `includeAssets` (in the same format) */
+  readonly includeAssets?: string[];
+  /** Assets to leave out. The build fails if anything that is included uses one of them. */
+  readonly excludeAssets?: string[];
+  /** Overrides the profile the build was started with */
+  readonly profile?: 'debug' | 'release';
+  /** Overrides the project's compression policy */
+  readonly compression?: CompressionPolicy;
+  /** Defines to add to (or override) the project's `defines` */
+  readonly defines?: Record<string, DefineValue>;
+}
+
+/**
+ * A logical name for one of the project's scenes.
+ * @NOTE This must match `NamedSceneReference` struct in: src/editor/src/app/src/filesystem/project.rs
+ */
+export interface NamedSceneReference {
+  readonly name: string;
+  /** ID of the scene (see {@link ProjectDefinition.scenes}) */
+  readonly id: string;
 }
@@ -1,10 +1,24 @@
 /* eslint-disable @typescript-eslint/no-unused-vars */
 import { unzip, zip } from 'fflate';
+import { parse as parseJsonc } from 'jsonc-parser';
+
+import { getFileExtension } from '@polyzone/runtime/src/util';
+import {
+  CARTRIDGE_FORMAT_VERSION,
+  CARTRIDGE_HEADER_FILENAME,
+  CARTRIDGE_MANIFEST_FILENAME,
+  CartridgeArchiveManifest,
+  CartridgeHeader,
+  ENGINE_VERSION,
+} from '@polyzone/runtime/src/cartridge/archive';
+import { AssetType, AssetTypeMap } from '@polyzone/runtime/src/cartridge/data';
 
 import { TauriCommandArgs, TauriCommandReturnType } from '@lib/util/TauriCommands';
+import { ProjectDefinition, SceneDefinition, toRuntimeSceneDefinition } from '@lib/project/definition';
 
 import { Paths } from "../config";
 import { promisify, throwUnhandled } from '../util';
+import { TauriPluginFsMockModule } from './TauriPluginFsMockModule';
 
 const unzipAsync = promisify(unzip);
 const zipAsync = promisify(zip);
@@ -15,6 +29,8 @@ export class PolyZoneMockModule {
     switch (action) {
       case 'create_cartridge':
         return this.mockCreateCartridge(args);
+      case 'cancel_create_cartridge':
+        return this.mockCancelCreateCartridge();
       case 'start_watching_project_files':
         return this.mockStartWatchingProjectFiles();
       case 'stop_watching_project_assets':
@@ -27,6 +43,8 @@ export class PolyZoneMockModule {
         return this.mockHashData(args);
       case 'notify_project_file_updated':
         return this.notifyProjectFileUpdated(args);
+      case 'get_script_property_schema':
+        return this.mockGetScriptPropertySchema(args);
       default:
         throw throwUnhandled(`[PolyZoneMockModule] (handle) Unimplemented action. (action='${action}') args: `, args);
     }
@@ -36,26 +54,123 @@ export class PolyZoneMockModule {
    * @NOTE
    * Building a cartridge depends on Rust backend to fully function.
    * This mock requires a sample cartridge (built by the full app) to exist somewhere (specified by {@link Paths.MockCartridgeFile}).
-   * This function reads that existing cartridge, replaces its manifest, and then
-   * serves that result. This means you can make changes in the Composer and see them
-   * reflected when playtesting, but changes to assets will not work.
+   * This function reads that existing cartridge, replaces its manifest with one assembled from the
+   * mock project (specified by {@link Paths.MockProjectFile}), and then "writes" that result
+   * to `outputPath` in memory (see {@link TauriPluginFsMockModule.writeInMemoryFile}).
+   * Since the mock file system cannot write files, changes made in the Composer will not be reflected when playtesting.
    * If you add or remove an asset the game likely won't even run. You will have to rebuild
    * the mock cartridge using the full app first.
    */
   public static async mockCreateCartridge(...args: TauriCommandArgs<'create_cartridge'>): Promise<TauriCommandReturnType<'create_cartridge'>> {
-    const { manifestFileBytes } = args[0];
+    const { entrySceneId, outputPath, options } = args[0];
+    if (options?.variant !== undefined) {
+      console.warn(`[PolyZoneMockModule] (create_cartridge) Tauri is mocked - build variants are not supported, so the whole project will be built`);
+    }
     const result = await fetch(Paths.MockCartridgeFile);
     if (result.ok) {
+      const header = await this.mockCreateCartridgeHeader(options?.profile ?? 'debug');
+      const manifest = await this.mockCreateCartridgeManifest(entrySceneId);
       const cartridgeBytes = await result.arrayBuffer();
       const cartridgeData = await unzipAsync(new Uint8Array(cartridgeBytes));
-      cartridgeData['manifest.json'] = new TextEncoder().encode(manifestFileBytes);
+      // @NOTE Keep the mock cartridge's deduplicated files, as they are not stored under their own path
+      const mockCartridgeManifest = JSON.parse(new TextDecoder().decode(cartridgeData[CARTRIDGE_MANIFEST_FILENAME])) as CartridgeArchiveManifest;
+      manifest.aliases = mockCartridgeManifest.aliases;
+      cartridgeData[CARTRIDGE_HEADER_FILENAME] = new TextEncoder().encode(JSON.stringify(header));
+      cartridgeData[CARTRIDGE_MANIFEST_FILENAME] = new TextEncoder().encode(JSON.stringify(manifest));
       const resultBytes = await zipAsync(cartridgeData);
-      return Array.from(resultBytes);
+      TauriPluginFsMockModule.writeInMemoryFile(outputPath, resultBytes);
+
+      // @NOTE Scripts in the mock cartridge are already built, so they are never minified
+      const scriptSize = manifest.assets
+        .filter((asset) => asset.type === AssetType.Script)
+        .reduce((total, asset) => total + (cartridgeData[asset.path]?.byteLength ?? 0), 0);
+      return { scriptSize, minifiedScriptSize: null, duplicateFileCount: 0, duplicateSize: 0, excludedAssets: [] };
     } else {
       throw throwUnhandled(`[PolyZoneMockModule] (create_cartridge) Failed fetching mock cartridge: `, result);
     }
   }
 
+  /**
+   * Assemble a cartridge header from the mock project, the same way the Rust backend does from the project on disk.
+   */
+  private static async mockCreateCartridgeHeader(buildProfile: CartridgeHeader['buildProfile']): Promise<CartridgeHeader> {
+    const result = await fetch(Paths.MockProjectFile);
+    if (!result.ok) {
+      throw throwUnhandled(`[PolyZoneMockModule] (create_cartridge) Failed fetching mock project file: `, result);
+    }
+    const { manifest } = parseJsonc(await result.text()) as ProjectDefinition;
+
+    return {
+      formatVersion: CARTRIDGE_FORMAT_VERSION,
+      title: manifest.projectName,
+      author: manifest.author ?? null,
+      description: manifest.description ?? null,
+      gameVersion: manifest.version ?? null,
+      minEngineVersion: manifest.minEngineVersion ?? ENGINE_VERSION,
+      buildTimestamp: Math.floor(Date.now() / 1000),
+      buildProfile,
+    };
+  }
+
+  /**
+   * Assemble a cartridge manifest from the mock project, the same way the Rust backend does from the project on disk.
+   */
+  private static async mockCreateCartridgeManifest(entrySceneId: string | undefined): Promise<CartridgeArchiveManifest> {
+    const fetchJsonc = async <T>(path: string): Promise<T> => {
+      const result = await fetch(path);
+      if (!result.ok) {
+        throw throwUnhandled(`[PolyZoneMockModule] (create_cartridge) Failed fetching mock project file: `, result);
+      }
+      return parseJsonc(await result.text()) as T;
+    };
+
+    const projectRootPath = Paths.MockProjectFile.replace(/[^/]*$/, '');
+    const projectDefinition = await fetchJsonc<ProjectDefinition>(Paths.MockProjectFile);
+
+    // Listed scenes come first (in the order they are listed), followed by any other scenes
+    const sceneList = projectDefinition.sceneList ?? [];
+    const getListPosition = (sceneId: string): number => {
+      const position = sceneList.findIndex((namedScene) => namedScene.id === sceneId);
+      return position === -1 ? sceneList.length : position;
+    };
+    const sceneManifests = [...projectDefinition.scenes].sort((a, b) => getListPosition(a.id) - getListPosition(b.id));
+
+    // Move the entry scene to be the first scene in the list
+    entrySceneId ??= projectDefinition.entryScene;
+    if (entrySceneId !== undefined) {
+      const entrySceneIndex = sceneManifests.findIndex((scene) => scene.id === entrySceneId);
+      if (entrySceneIndex === -1) {
+        throw new Error(`Cannot build cartridge. Cannot set entrypoint to SceneDefinition with ID '${entrySceneId}' - it isn't one of the current project's scenes`);
+      }
+      sceneManifests.unshift(...sceneManifests.splice(entrySceneIndex, 1));
+    }
+
+    return {
+      assets: projectDefinition.assets.map((asset) => {
+        const fileExtension = getFileExtension(asset.path);
+        const type = (Object.keys(AssetTypeMap) as AssetType[]).find((type) => AssetTypeMap[type].includes(fileExtension)) ?? AssetType.Unknown;
+        return {
+          id: asset.id,
+          type,
+          // @NOTE Scripts need to be renamed to .js
+          path: type === AssetType.Script ? asset.path.replace(/\.\w+$/, '.js') : asset.path,
+        };
+      }),
+      scenes: await Promise.all(sceneManifests.map(async (scene) =>
+        toRuntimeSceneDefinition(await fetchJsonc<SceneDefinition>(`${projectRootPath}${scene.path}`), scene.path),
+      )),
+      entryScene: sceneManifests[0]?.path,
+      sceneList: sceneList.map((namedScene) => ({
+        name: namedScene.name,
+        path: projectDefinition.scenes.find((scene) => scene.id === namedScene.id)?.path ?? '',
+      })),
+    };
+  }
+
+  public static async mockCancelCreateCartridge(..._args: TauriCommandArgs<'cancel_create_cartridge'>): Promise<TauriCommandReturnType<'cancel_create_cartridge'>> {
+    // @NOTE No-op. Mock builds are not cancellable.
+  }
+
   public static async mockLoadProject(..._args: TauriCommandArgs<'load_project'>): Promise<TauriCommandReturnType<'load_project'>> {
     // @NOTE No-op.
   }
@@ -80,4 +195,9 @@ export class PolyZoneMockModule {
   public static async notifyProjectFileUpdated(...args: TauriCommandArgs<'notify_project_file_updated'>): Promise<TauriCommandReturnType<'notify_project_file_updated'>> {
     // @NOTE No-op.
   }
+
+  public static async mockGetScriptPropertySchema(..._args: TauriCommandArgs<'get_script_property_schema'>): Promise<TauriCommandReturnType<'get_script_property_schema'>> {
+    // @NOTE Scripts cannot be analysed without the Rust backend, so no fields are editable
+    return { properties: [] };
+  }
 }
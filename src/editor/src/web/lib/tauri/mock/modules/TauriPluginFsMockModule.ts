@@ -7,6 +7,9 @@ import { DebouncedWatchOptions, WatchEvent } from '@tauri-apps/plugin-fs';
 import { Channel } from '@tauri-apps/api/core';
 
 export class TauriPluginFsMockModule {
+  /** Files written by other mocks (e.g. cartridges built by `create_cartridge`), kept in memory */
+  private static readonly inMemoryFiles = new Map<string, Uint8Array>();
+
   // eslint-disable-next-line @typescript-eslint/explicit-function-return-type
   public static handle(action: string, args: any) {
     switch (action) {
@@ -32,7 +35,10 @@ export class TauriPluginFsMockModule {
     if (path instanceof URL) {
       throw throwUnhandled(`[TauriPluginFsMockModule] (readFile) Unimplemented - 'path' is instance of URL: `, path, options);
     }
-    if (path.startsWith(Paths.MagicFileRoot)) {
+    const inMemoryFile = this.inMemoryFiles.get(path);
+    if (inMemoryFile !== undefined) {
+      return inMemoryFile;
+    } else if (path.startsWith(Paths.MagicFileRoot)) {
       const result = await fetch(path.replace(Paths.MagicFileRoot, ''));
       if (result.ok) {
         const buffer = await result.arrayBuffer();
@@ -45,6 +51,14 @@ export class TauriPluginFsMockModule {
     }
   };
 
+  /**
+   * Store a file in memory, so that it can be read back with `readFile`.
+   * For use by other mocks that produce files.
+   */
+  public static writeInMemoryFile(path: string, data: Uint8Array): void {
+    this.inMemoryFiles.set(path, data);
+  }
+
   private static writeFile(data: Uint8Array): void {
     console.warn(`[TauriPluginFsMockModule] (writeFile) Tauri is mocked - no file actually written`);
   }
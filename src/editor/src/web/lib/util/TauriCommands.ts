@@ -1,17 +1,182 @@
 import { invoke as tauriInvoke } from '@tauri-apps/api/core';
 
+import type { CartridgeArchiveManifest, CartridgeHeader } from '@polyzone/runtime/src/cartridge/archive';
+import type { AssetType } from '@polyzone/runtime/src/cartridge/data';
+
+/**
+ * A single problem found while building a cartridge.
+ * `line` and `column` are 1-based, and only present if the problem has a location in the file.
+ */
+export interface BuildDiagnostic {
+  path: string;
+  severity: 'error' | 'warning';
+  message: string;
+  line: number | null;
+  column: number | null;
+  snippet: string | null;
+}
+
+/**
+ * Error thrown by `create_cartridge` when a cartridge is not built.
+ * @NOTE This must match `BuildError` enum in: src/editor/src/app/src/build/diagnostics.rs
+ */
+export type BuildError = {
+  type: 'failed';
+  diagnostics: BuildDiagnostic[];
+} | {
+  type: 'cancelled';
+};
+
+/**
+ * Payload of the `build_progress` event, emitted after each file is added to a cartridge.
+ */
+export interface BuildProgress {
+  current: number;
+  total: number;
+  path: string;
+}
+
+/**
+ * Which kind of cartridge to build.
+ * - `debug`: Scripts are compiled with source maps
+ * - `release`: For distributing the game. Scripts are minified
+ */
+export type BuildProfile = 'debug' | 'release';
+
+/**
+ * Options controlling how a cartridge is built.
+ * @NOTE This must match `BuildOptions` struct in: src/editor/src/app/src/build.rs
+ */
+export interface BuildOptions {
+  /** Default: `debug` */
+  profile?: BuildProfile;
+  /** Store the original source of each script in its source map. Only applies to debug builds. */
+  embedScriptSources?: boolean;
+  /** Sign the cartridge with the ed25519 key stored in this file */
+  signingKeyPath?: string;
+  /**
+   * Time (in seconds since the Unix epoch) recorded as when the cartridge was built, instead of the current time.
+   * Builds of the same project with the same timestamp are byte-identical.
+   */
+  buildTimestamp?: number;
+  /** Name of the build variant (defined in the project file) to build, instead of the whole project */
+  variant?: string;
+}
+
+/**
+ * Summary of a finished build, returned by `create_cartridge`.
+ * Sizes are in bytes.
+ */
+export interface BuildReport {
+  scriptSize: number;
+  /** Only present if scripts were minified (i.e. release builds) */
+  minifiedScriptSize: number | null;
+  /** Number of files not stored in the cartridge, because an identical file already was */
+  duplicateFileCount: number;
+  /** Total size of those files, uncompressed */
+  duplicateSize: number;
+  /** Paths of assets in the project that were left out of the cartridge, because nothing uses them */
+  excludedAssets: string[];
+}
+
+/**
+ * A field of a script component that can be edited in the inspector.
+ * `defaultValue` is only present if the field is initialised to a constant in the script.
+ * @NOTE This must match `ScriptProperty` struct in: src/editor/src/app/src/build/properties.rs
+ */
+export type ScriptProperty = { name: string } & (
+  { type: 'number'; defaultValue: number | null; } |
+  { type: 'string'; defaultValue: string | null; } |
+  { type: 'boolean'; defaultValue: boolean | null; } |
+  { type: 'vector3'; defaultValue: { x: number; y: number; z: number; } | null; } |
+  { type: 'color3'; defaultValue: { r: number; g: number; b: number; } | null; } |
+  /** `assetType` is null if any type of asset can be referenced */
+  { type: 'asset'; assetType: AssetType | null; defaultValue: null; }
+);
+
+/**
+ * The fields of a script component that can be edited in the inspector,
+ * returned by `get_script_property_schema`.
+ */
+export interface ScriptPropertySchema {
+  properties: ScriptProperty[];
+}
+
+/**
+ * Payload of the `on_script_property_schemas_updated` event, emitted (as a list) when script assets are modified.
+ * @NOTE This must match `ScriptPropertySchemaEvent` struct in: src/editor/src/app/src/filesystem/assets.rs
+ */
+export interface ScriptPropertySchemaEvent {
+  assetId: string;
+  schema: ScriptPropertySchema;
+}
+
+/**
+ * Whether a cartridge is signed, and if so whether the signature can be trusted.
+ * - `untrusted`: Valid, but not made by the expected key
+ * @NOTE This must match `SignatureStatus` enum in: src/editor/src/app/src/cartridge/integrity.rs
+ */
+export type SignatureStatus =
+  { type: 'unsigned' } |
+  { type: 'valid'; publicKey: string; } |
+  { type: 'untrusted'; publicKey: string; } |
+  { type: 'invalid'; reason: string; };
+
+/**
+ * Result of checking a cartridge's files against the hashes in its manifest, returned by `verify_cartridge`.
+ * @NOTE This must match `CartridgeVerification` struct in: src/editor/src/app/src/cartridge/integrity.rs
+ */
+export interface CartridgeVerification {
+  signature: SignatureStatus;
+  verifiedEntryCount: number;
+  failedEntries: {
+    path: string;
+    problem: 'missing' | 'modified' | 'unlisted';
+  }[];
+}
+
+/**
+ * A single file within a cartridge. Sizes are in bytes.
+ * @NOTE This must match `CartridgeEntry` struct in: src/editor/src/app/src/cartridge/reader.rs
+ */
+export interface CartridgeEntry {
+  path: string;
+  size: number;
+  compressedSize: number;
+  /** e.g. `Deflated`, `Stored` */
+  compressionMethod: string;
+  /** `compressedSize` as a fraction of `size` (i.e. lower is better) */
+  compressionRatio: number;
+}
+
+/**
+ * The contents of a cartridge, returned by `inspect_cartridge`.
+ * @NOTE This must match `CartridgeInspection` struct in: src/editor/src/app/src/cartridge/reader.rs
+ */
+export interface CartridgeInspection {
+  /** `null` for cartridges built before headers were written */
+  header: CartridgeHeader | null;
+  manifest: CartridgeArchiveManifest;
+  entries: CartridgeEntry[];
+  /** Problems with the manifest that would stop the cartridge from loading */
+  problems: string[];
+}
+
 /**
  * A list of all commands available in Tauri, as well
  * as their params and return types.
  * Use {@link invoke} to call these methods in a typesafe manner.
  */
 type TauriCommands = {
+  /** @throws {BuildError} */
   create_cartridge(args: {
-    manifestFileBytes: string;
     projectRootPath: string;
-    assetPaths: string[];
-    scriptPaths: string[];
-  }): number[],
+    entrySceneId?: string;
+    outputPath: string;
+    options?: BuildOptions;
+  }): BuildReport,
+
+  cancel_create_cartridge(): void,
 
   load_project(args: {
     projectFilePath: string;
@@ -30,6 +195,23 @@ type TauriCommands = {
   notify_project_file_updated(args: {
     data: number[],
   }): void;
+
+  /** @throws {BuildDiagnostic} if the script cannot be read or parsed */
+  get_script_property_schema(args: {
+    scriptPath: string,
+  }): ScriptPropertySchema;
+
+  /** @throws {string} if the cartridge cannot be read, or has no hashes to check */
+  verify_cartridge(args: {
+    cartridgePath: string,
+    /** If given, the cartridge must be signed by this (hex-encoded) key */
+    trustedPublicKey?: string,
+  }): CartridgeVerification;
+
+  /** @throws {string} if the cartridge cannot be read, or its manifest cannot be parsed */
+  inspect_cartridge(args: {
+    cartridgePath: string,
+  }): CartridgeInspection;
 }
 
 export type TauriCommandArgs<T extends keyof TauriCommands> = Parameters<TauriCommands[T]>;
@@ -1,6 +1,8 @@
 // @TODO Refactor to work like `TauriCommands`
 export enum TauriEvents {
+  BuildProgress = 'build_progress',
   OnProjectAssetsUpdated = 'on_project_assets_updated',
   OnProjectFileUpdated = 'on_project_file_updated',
   OnProjectScenesUpdated = 'on_project_scenes_updated',
+  OnScriptPropertySchemasUpdated = 'on_script_property_schemas_updated',
 }
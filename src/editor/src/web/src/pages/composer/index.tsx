@@ -3,7 +3,8 @@ import { useEffect, useRef, useState } from "react";
 import { observer } from "mobx-react-lite";
 import Link from "next/link";
 import { save } from '@tauri-apps/plugin-dialog';
-import { writeFile } from '@tauri-apps/plugin-fs';
+import { readFile } from '@tauri-apps/plugin-fs';
+import { join, tempDir } from '@tauri-apps/api/path';
 import { PlayIcon, StopIcon, ArrowLeftEndOnRectangleIcon, CubeIcon, PlusIcon, XMarkIcon } from '@heroicons/react/24/solid';
 import { Panel, PanelGroup, PanelResizeHandle } from "react-resizable-panels";
 
@@ -54,8 +55,6 @@ const ComposerPage: FunctionComponent<Props> = observer(({ }) => {
 
   // Functions
   const debug_exportScene = async (): Promise<void> => {
-
-    const bytes = await ComposerController.debug_buildCartridge();
     const savePath = await save({
       filters: [{
         name: 'PolyZone Cartridge',
@@ -64,12 +63,21 @@ const ComposerPage: FunctionComponent<Props> = observer(({ }) => {
     });
     if (!savePath) return;
 
-    await writeFile(savePath, bytes);
+    await ComposerController.debug_buildCartridge(savePath, undefined, (progress) => {
+      console.log(`[Composer] (debug_exportScene) Building cartridge [${progress.current}/${progress.total}] ${progress.path}`);
+    });
   };
 
   const debug_playProject = async (): Promise<void> => {
     const currentlyFocusedTab = ComposerController.currentlyOpenTabs.find((tab) => tab.id === TabState.currentTabPageId);
-    const bytes = await ComposerController.debug_buildCartridge(currentlyFocusedTab?.sceneViewController?.scene.id);
+    // @NOTE Cartridge is built to a temporary file and then read back into memory
+    const tempCartridgePath = await join(await tempDir(), 'polyzone-playtest.pzcart');
+    // @NOTE Playtest builds include source maps, so that scripts can be debugged in devtools
+    await ComposerController.debug_buildCartridge(tempCartridgePath, currentlyFocusedTab?.sceneViewController?.scene.id, undefined, {
+      profile: 'debug',
+      embedScriptSources: true,
+    });
+    const bytes = await readFile(tempCartridgePath);
     setTempCartridge(bytes);
   };
 
@@ -8,6 +8,8 @@ import path from 'path';
 
 /** Path to write generated bindings to */
 const OutputPath = 'src/core.g.ts';
+/** Path to write the list of core modules for the editor's build pipeline to */
+const EditorOutputPath = '../editor/src/app/src/build/core_modules.rs';
 /** Root import alias of all modules in the core project */
 const ImportAliasRoot = '@polyzone/core';
 
@@ -57,6 +59,26 @@ await writeFile(OutputPath, fileContents);
 
 console.log(`Successfully wrote ${OutputPath} (${fileContents.length} bytes)`);
 
+// The editor validates script imports when building a cartridge, so it needs to know the names of all core modules too
+let editorFileContents = `/* *******************
+ * AUTO-GENERATED FILE
+ * *******************
+ * @NOTE This file is automatically generated from the contents of \`${ImportAliasRoot}\`
+ * To regenerate this file, run \`npm run build:core.g\` in the runtime project
+ *
+ * DO NOT EDIT THIS FILE MANUALLY, AS ANY CHANGES TO IT WILL BE OVERWRITTEN.
+ */
+
+/// Names of all the core modules that can be imported by scripts
+pub const CORE_MODULES: [&str; ${modules.length}] = [
+${modules.map((module) => `    "${module.publicImport}",`).join('\n')}
+];
+`;
+
+await writeFile(EditorOutputPath, editorFileContents);
+
+console.log(`Successfully wrote ${EditorOutputPath} (${editorFileContents.length} bytes)`);
+
 /**
  * Turn a path like `world/GameObject.ts` into a unique
  * variable name like `WorldGameObject`.
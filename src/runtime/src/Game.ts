@@ -73,22 +73,30 @@ export class Game {
     // @TODO unload previous cartridge
     this.cartridge = cartridge;
 
-    // Load all scripts from the cartridge
+    // Load all scripts (and modules from npm packages they import) from the cartridge
     // We do this proactively because scripts can depend on other scripts
     // which need to be injected when they are requested
     await Promise.all(cartridge.assetDb.assets
       .filter((asset) => asset.type === AssetType.Script)
       .map((asset) =>
-        cartridge.assetDb.loadAsset(asset)
+        Promise.all([
+          cartridge.assetDb.loadAsset(asset),
+          cartridge.assetDb.loadSourceMap(asset),
+        ])
+          .then(([file, sourceMapFile]) => {
+            this.scriptLoader.loadModule(asset, file, sourceMapFile);
+          })
+      )
+      .concat(cartridge.vendorModules.map((path) =>
+        cartridge.assetDb.fileSystem.readFile(path)
           .then((file) => {
-            this.scriptLoader.loadModule(asset, file);
+            this.scriptLoader.loadVendorModule(path, file);
           })
-      ))
+      )))
 
 
-    // Load the first scene on the cartridge
-    // @TODO add concept of "initial" scene to cartridge manifest
-    await this.loadCartridgeScene(cartridge.sceneDb.allScenes[0]);
+    // Load the cartridge's entry scene
+    await this.loadCartridgeScene(cartridge.sceneDb.entryScene);
   }
 
   /**
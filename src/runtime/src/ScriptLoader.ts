@@ -3,6 +3,11 @@ import { CoreModules } from './core.g';
 import { VirtualFile } from './filesystem';
 
 const SCRIPT_PATH_PREFIX = `scripts/`;
+/**
+ * Number of lines that come before a script's code when it is loaded
+ * i.e. the header added by `new Function()` (`function anonymous(define\n) {\n`), plus the `"use strict"` line.
+ */
+const SCRIPT_HEADER_LINE_COUNT = 3;
 
 /**
  * Metadata of a script module
@@ -42,22 +47,45 @@ export class ScriptLoader {
 
   /**
    * Load a script module from a {@link VirtualFile} into the cache.
-   * @param scriptFile The script file to load.
+   * @param scriptAsset The script file to load.
+   * @param file The contents of the script file.
+   * @param sourceMapFile Source map for the script (if any), to show the original source in devtools.
    */
-  public loadModule(scriptAsset: AssetData, file: VirtualFile) {
+  public loadModule(scriptAsset: AssetData, file: VirtualFile, sourceMapFile: VirtualFile | undefined = undefined) {
     if (scriptAsset.type !== AssetType.Script) {
       throw new Error(`Cannot load non-script asset as module: ${scriptAsset}`);
     }
 
-    let moduleId = this.pathToModuleId(scriptAsset.path);
+    this.evaluateModule(this.pathToModuleId(scriptAsset.path), scriptAsset.path, file, sourceMapFile);
+  }
+
+  /**
+   * Load a module from an npm package (compiled into the cartridge alongside its scripts) into the cache.
+   * Its ID is its path in the cartridge e.g. `node_modules/easing-utils/dist/index.js`,
+   * which is what imports of it are rewritten to when the cartridge is built.
+   * @param path Path of the module in the cartridge.
+   * @param file The contents of the module.
+   */
+  public loadVendorModule(path: string, file: VirtualFile) {
+    this.evaluateModule(path, path, file, undefined);
+  }
+
+  /**
+   * Evaluate a compiled AMD module, storing its definition under `moduleId`.
+   */
+  private evaluateModule(moduleId: string, path: string, file: VirtualFile, sourceMapFile: VirtualFile | undefined) {
     if (this.moduleDefinitions[moduleId] !== undefined) {
       // @TODO just no-op / warn
-      throw new Error(`Tried to load duplicate module: ${scriptAsset.path}`)
+      throw new Error(`Tried to load duplicate module: ${path}`)
     }
 
     let moduleDefinition: ModuleDefinition = undefined!;
     // @NOTE use magic "source map" keyword `sourceURL` to make script show up in devtools sources under `cartridge/`
-    new Function('define', `"use strict";\n${file.textContent}\n//# sourceURL=cartridge/${scriptAsset.path}`)(this.defineModule.bind(this, (result) => {
+    let source = `"use strict";\n${file.textContent}\n//# sourceURL=cartridge/${path}`;
+    if (sourceMapFile !== undefined) {
+      source += `\n//# sourceMappingURL=${this.createSourceMapUrl(sourceMapFile)}`;
+    }
+    new Function('define', source)(this.defineModule.bind(this, (result) => {
       moduleDefinition = result;
     }));
 
@@ -65,7 +93,7 @@ export class ScriptLoader {
       throw new Error("Defining module did not produce a result");
     } else {
       moduleDefinition.id = moduleId;
-      console.log(`Loaded module '${moduleId}' (from path: '${scriptAsset.path}')`, moduleDefinition);
+      console.log(`Loaded module '${moduleId}' (from path: '${path}')`, moduleDefinition);
       this.moduleDefinitions[moduleId] = moduleDefinition;
     }
   }
@@ -115,7 +143,7 @@ export class ScriptLoader {
             // @NOTE magic dependency id for declaring the module's contents on
             return module;
           } else {
-            return this.getModuleById(dependencyId);
+            return this.getModuleById(this.resolveModuleId(dependencyId, moduleId));
           }
         })
 
@@ -130,6 +158,43 @@ export class ScriptLoader {
     return this.moduleCache[moduleId];
   }
 
+  /**
+   * Create a data URL for a script's source map, that can be referenced by the script once it is loaded.
+   * Source maps are relative to the compiled script, so they are offset to account for
+   * the lines that are added to the script when it is loaded.
+   */
+  private createSourceMapUrl(sourceMapFile: VirtualFile): string {
+    const sourceMap = JSON.parse(sourceMapFile.textContent);
+    // @NOTE Each `;` in `mappings` is a line with no mappings
+    sourceMap.mappings = ';'.repeat(SCRIPT_HEADER_LINE_COUNT) + sourceMap.mappings;
+    return `data:application/json;charset=utf-8,${encodeURIComponent(JSON.stringify(sourceMap))}`;
+  }
+
+  /**
+   * Resolve a module ID imported by another module.
+   * Relative IDs (e.g. `../util/Math`) are relative to the importing module (e.g. `./player/Player` => `./util/Math`).
+   * Any other IDs (e.g. core modules, or modules from npm packages) are returned as-is.
+   * @NOTE Must match `resolve_relative_specifier()` in: src/editor/src/app/src/build/imports.rs
+   * @param dependencyId ID of the module being imported.
+   * @param moduleId ID of the module doing the importing.
+   */
+  private resolveModuleId(dependencyId: string, moduleId: string): string {
+    if (!dependencyId.startsWith('./') && !dependencyId.startsWith('../')) {
+      return dependencyId;
+    }
+
+    // Relative to the directory the importing module is in
+    const segments = moduleId.split('/').slice(1, -1);
+    for (const segment of dependencyId.split('/')) {
+      if (segment === '..') {
+        segments.pop();
+      } else if (segment !== '.' && segment !== '') {
+        segments.push(segment);
+      }
+    }
+    return `./${segments.join('/')}`;
+  }
+
   /**
    * Convert a file path to a module ID.
    */
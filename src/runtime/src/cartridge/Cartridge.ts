@@ -1,16 +1,22 @@
 import { SceneDb } from "./data/scenes";
 import { AssetDb } from "./data/assets";
+import { CartridgeHeader } from "./archive/CartridgeHeader";
 
 /**
  * A loaded game Cartridge, containing all the data for the entire game.
  */
 export class Cartridge {
-  // @TODO game manifest (e.g. game title)
+  /** Metadata about the game (e.g. its title). `undefined` for cartridges built before headers were introduced. */
+  public readonly header: CartridgeHeader | undefined;
   public readonly sceneDb: SceneDb;
   public readonly assetDb: AssetDb;
+  /** Paths of modules from npm packages that scripts import, which are also their module IDs */
+  public readonly vendorModules: string[];
 
-  public constructor(sceneDb: SceneDb, assetDb: AssetDb) {
+  public constructor(header: CartridgeHeader | undefined, sceneDb: SceneDb, assetDb: AssetDb, vendorModules: string[]) {
+    this.header = header;
     this.sceneDb = sceneDb;
     this.assetDb = assetDb;
+    this.vendorModules = vendorModules;
   }
 }
\ No newline at end of file
@@ -5,4 +5,19 @@
 export interface AssetDefinition {
   id: string;
   path: string;
+  /** For scripts that default-export a `ScriptComponent` class, metadata about that class */
+  scriptComponent?: ScriptComponentClassDefinition;
+  /** IDs of other assets this asset refers to e.g. the materials used by a mesh, or the textures used by a material */
+  dependencies?: string[];
+}
+
+/**
+ * Metadata about a script's default-exported `ScriptComponent` class.
+ * Recorded when the cartridge is built.
+ */
+export interface ScriptComponentClassDefinition {
+  /** Name of the class. `null` if the class is anonymous. */
+  className: string | null;
+  /** Lifecycle methods (e.g. `onUpdate`) that the class overrides */
+  lifecycleMethods: string[];
 }
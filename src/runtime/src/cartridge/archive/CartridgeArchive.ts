@@ -3,6 +3,7 @@ import { Unzipped } from "fflate";
 import { CartridgeFileSystem } from '@polyzone/runtime/src/filesystem';
 
 import { CartridgeArchiveManifest } from "./CartridgeArchiveManifest";
+import { CARTRIDGE_HEADER_FILENAME, CartridgeHeader } from "./CartridgeHeader";
 
 export const CARTRIDGE_MANIFEST_FILENAME = 'manifest.json';
 
@@ -14,6 +15,26 @@ export class CartridgeArchive {
 
   public constructor(unzippedData: Unzipped) {
     this.fileSystem = new CartridgeFileSystem(unzippedData);
+
+    // @NOTE Deduplicated files are only stored once, so make them readable at every path they were deduplicated from
+    if (this.fileSystem.exists(CARTRIDGE_MANIFEST_FILENAME)) {
+      for (const alias of this.manifest.aliases ?? []) {
+        unzippedData[alias.path] = unzippedData[alias.target];
+      }
+    }
+  }
+
+  /**
+   * Metadata identifying the cartridge (e.g. the title of the game).
+   * `undefined` if the cartridge was built before headers were introduced.
+   */
+  public get header(): CartridgeHeader | undefined {
+    if (!this.fileSystem.exists(CARTRIDGE_HEADER_FILENAME)) {
+      return undefined;
+    }
+    const file = this.fileSystem.readFileSync(CARTRIDGE_HEADER_FILENAME);
+    const json = new TextDecoder().decode(file.bytes);
+    return JSON.parse(json) as CartridgeHeader;
   }
 
   /**
@@ -8,7 +8,59 @@ import { SceneDefinition } from "./SceneDefinition";
  * i.e. the definition of the Cartridge on-disk, before being loaded by the engine.
  */
 export interface CartridgeArchiveManifest {
-  // @TODO any metadata like THE TITLE OF THE GAME? Lol
+  /** Every scene in the Cartridge. The entry scene is always first. */
   scenes: SceneDefinition[];
   assets: AssetDefinition[];
+  /**
+   * Path of the scene the game starts in.
+   * Not present in Cartridges built before entry scenes were recorded (which start in the first scene).
+   */
+  entryScene?: string;
+  /** Logical names for scenes, in the order the project lists them, for switching between scenes at runtime */
+  sceneList?: NamedSceneDefinition[];
+  /**
+   * Paths of modules from npm packages that scripts import (compiled like scripts), which are also their module IDs.
+   * Not present in Cartridges without any.
+   */
+  vendorModules?: string[];
+  /**
+   * Hashes of every other file in the Cartridge, for detecting corrupt or modified Cartridges.
+   * Not present in Cartridges built before hashes were recorded.
+   */
+  entries?: ArchiveEntryHash[];
+  /**
+   * Files with exactly the same contents as another file in the Cartridge, which are only stored once.
+   * {@link CartridgeArchive} makes these readable at their own path.
+   */
+  aliases?: ArchiveEntryAlias[];
+}
+
+/**
+ * A logical name for a scene in the Cartridge.
+ */
+export interface NamedSceneDefinition {
+  name: string;
+  /** Path of the scene (see {@link SceneDefinition.path}) */
+  path: string;
+}
+
+/**
+ * A file that is not stored in the Cartridge, because it has the same contents as another file that is.
+ */
+export interface ArchiveEntryAlias {
+  path: string;
+  /** Path of the file (with the same contents) that is stored in the Cartridge */
+  target: string;
+}
+
+/**
+ * Hashes of the (uncompressed) contents of a file in a Cartridge.
+ */
+export interface ArchiveEntryHash {
+  path: string;
+  size: number;
+  /** xxHash3 (64-bit), hex-encoded */
+  xxh3: string;
+  /** SHA-256, hex-encoded */
+  sha256: string;
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+import type { CartridgeArchive } from './CartridgeArchive';
+
+export const CARTRIDGE_HEADER_FILENAME = 'header.json';
+/**
+ * Version of the cartridge format that this runtime can load.
+ * @NOTE Should be kept in-sync with `CARTRIDGE_FORMAT_VERSION` in: src/editor/src/app/src/cartridge/header.rs
+ */
+export const CARTRIDGE_FORMAT_VERSION = 1;
+/**
+ * Version of this runtime (i.e. the engine).
+ * @NOTE Should be kept in-sync with `ENGINE_VERSION` in: src/editor/src/app/src/cartridge/header.rs
+ */
+export const ENGINE_VERSION = '0.1.0';
+
+/**
+ * Metadata identifying a {@link CartridgeArchive}, stored separately from its manifest.
+ * Cartridges built before headers were introduced do not have one.
+ */
+export interface CartridgeHeader {
+  /** Version of the format of the cartridge's contents. See {@link CARTRIDGE_FORMAT_VERSION}. */
+  formatVersion: number;
+  /** Title of the game */
+  title: string;
+  author: string | null;
+  description: string | null;
+  /** Version of the game e.g. `1.2.0` */
+  gameVersion: string | null;
+  /** Oldest version of the engine that can play the cartridge e.g. `0.1.0` */
+  minEngineVersion: string;
+  /** When the cartridge was built, in seconds since the Unix epoch */
+  buildTimestamp: number;
+  buildProfile: 'debug' | 'release';
+  /** Name of the build variant the Cartridge was built from (e.g. `demo`). Not present if the whole project was built. */
+  buildVariant?: string;
+}
+
+/**
+ * Check that a cartridge can be played by this runtime.
+ * @throws If the cartridge was built for an incompatible format or a newer engine.
+ */
+export function validateCartridgeHeader(header: CartridgeHeader): void {
+  if (header.formatVersion !== CARTRIDGE_FORMAT_VERSION) {
+    throw new Error(`Cannot load cartridge '${header.title}'. It uses cartridge format version ${header.formatVersion}, but this player only supports version ${CARTRIDGE_FORMAT_VERSION}`);
+  }
+  if (compareEngineVersions(header.minEngineVersion, ENGINE_VERSION) > 0) {
+    throw new Error(`Cannot load cartridge '${header.title}'. It requires engine version ${header.minEngineVersion} or newer, but this player is version ${ENGINE_VERSION}`);
+  }
+}
+
+/**
+ * Compare two engine versions (e.g. `0.1.0`).
+ * @returns A negative number if `a` is older than `b`, a positive number if `a` is newer than `b`, otherwise 0.
+ */
+function compareEngineVersions(a: string, b: string): number {
+  const aParts = a.split('.').map(Number);
+  const bParts = b.split('.').map(Number);
+  for (let i = 0; i < Math.max(aParts.length, bParts.length); i++) {
+    const difference = (aParts[i] ?? 0) - (bParts[i] ?? 0);
+    if (difference !== 0) {
+      return difference;
+    }
+  }
+  return 0;
+}
@@ -3,5 +3,6 @@ export * from './util';
 export * from './AssetDefinition';
 export * from './CartridgeArchive';
 export * from './CartridgeArchiveManifest';
+export * from './CartridgeHeader';
 export * from './SceneDefinition';
 export * from './GameObjectDefinition';
@@ -14,8 +14,8 @@ import { AssetType } from './AssetType';
 // @TODO Could we get this data from the rust backend
 export const AssetTypeMap: Record<AssetType, string[]> = {
   [AssetType.Mesh]: ['.obj', '.fbx', '.gltf', '.glb', '.stl'],
-  [AssetType.MeshSupplementary]: ['.mtl'],
-  [AssetType.Script]: ['.ts', '.js'],
+  [AssetType.MeshSupplementary]: ['.mtl', '.bin'],
+  [AssetType.Script]: ['.ts', '.tsx', '.js', '.jsx'],
   [AssetType.Sound]: ['.mp3', '.ogg', '.wav'],
   [AssetType.Texture]: ['.png', '.jpg', '.jpeg', '.bmp', '.basis', '.dds'],
   [AssetType.Unknown]: []
@@ -59,6 +59,18 @@ export class AssetDb {
     return this.fileSystem.readFile(asset.path);
   }
 
+  /**
+   * Load the source map for an asset (i.e. a compiled script), if there is one.
+   * Source maps are only present in debug cartridges.
+   */
+  public async loadSourceMap(asset: AssetData): Promise<VirtualFile | undefined> {
+    try {
+      return await this.fileSystem.readFile(`${asset.path}.map`);
+    } catch {
+      return undefined;
+    }
+  }
+
   /**
    * Resolve the type of asset.
    * @param asset
@@ -1,14 +1,29 @@
-import type { SceneDefinition } from '../../archive';
+import type { NamedSceneDefinition, SceneDefinition } from '../../archive';
 import { AssetDb } from '../assets/AssetDb';
 import { SceneData } from './SceneData';
 
 export class SceneDb {
   private scenes: SceneData[];
+  private entryScenePath: string | undefined;
+  private sceneList: NamedSceneDefinition[];
 
-  public constructor(sceneDefinitions: SceneDefinition[], assetDb: AssetDb) {
+  public constructor(sceneDefinitions: SceneDefinition[], assetDb: AssetDb, entryScenePath?: string, sceneList?: NamedSceneDefinition[]) {
     this.scenes = sceneDefinitions.map((sceneDefinition) =>
       new SceneData(sceneDefinition, assetDb)
     );
+    this.entryScenePath = entryScenePath;
+    this.sceneList = sceneList ?? [];
+  }
+
+  /**
+   * Look up a scene by the logical name given to it in the project's scene list.
+   */
+  public getByName(name: string): SceneData {
+    const namedScene = this.sceneList.find((namedScene) => namedScene.name === name);
+    if (namedScene === undefined) {
+      throw new Error(`No scene named: ${name}`);
+    }
+    return this.getByPath(namedScene.path);
   }
 
   public getByPathSuffix(pathSuffix: string): SceneData {
@@ -32,7 +47,33 @@ export class SceneDb {
     }
   }
 
+  /**
+   * The scene the game starts in.
+   */
+  public get entryScene(): SceneData {
+    // @NOTE Cartridges built before entry scenes were recorded start in the first scene
+    if (this.entryScenePath === undefined) {
+      return this.scenes[0];
+    }
+    return this.getByPath(this.entryScenePath);
+  }
+
   public get allScenes(): SceneData[] {
     return this.scenes;
   }
+
+  /**
+   * Names of every scene in the project's scene list, in order.
+   */
+  public get sceneNames(): string[] {
+    return this.sceneList.map((namedScene) => namedScene.name);
+  }
+
+  private getByPath(path: string): SceneData {
+    const scene = this.scenes.find((scene) => scene.path === path);
+    if (scene === undefined) {
+      throw new Error(`No scene with path: ${path}`);
+    }
+    return scene;
+  }
 }
\ No newline at end of file
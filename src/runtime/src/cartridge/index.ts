@@ -5,6 +5,7 @@ export * from './data';
 export * from './Cartridge';
 
 import { CartridgeArchive } from './archive/CartridgeArchive';
+import { validateCartridgeHeader } from './archive/CartridgeHeader';
 import { AssetDb, SceneDb } from './data';
 import { Cartridge } from './Cartridge';
 
@@ -39,11 +40,18 @@ export async function fetchCartridge(url: string): Promise<CartridgeArchive> {
  * @param cartridgeArchive {@link CartridgeArchive} file to load.
  */
 export async function loadCartridge(cartridgeArchive: CartridgeArchive): Promise<Cartridge> {
+  const cartridgeHeader = cartridgeArchive.header;
+  if (cartridgeHeader !== undefined) {
+    validateCartridgeHeader(cartridgeHeader);
+  } else {
+    console.warn(`[loadCartridge] Cartridge has no header. It was built by an older version of PolyZone and may not load correctly`);
+  }
+
   // @TODO validate DTO
   const cartridgeManifest = cartridgeArchive.manifest;
 
   const assetDb = new AssetDb(cartridgeManifest.assets, cartridgeArchive.fileSystem);
-  const sceneDb = new SceneDb(cartridgeManifest.scenes, assetDb);
+  const sceneDb = new SceneDb(cartridgeManifest.scenes, assetDb, cartridgeManifest.entryScene, cartridgeManifest.sceneList);
 
-  return new Cartridge(sceneDb, assetDb);
+  return new Cartridge(cartridgeHeader, sceneDb, assetDb, cartridgeManifest.vendorModules ?? []);
 }
@@ -18,6 +18,7 @@ import * as WorldComponentsMeshComponent from '@polyzone/core/src/world/componen
 import * as WorldComponentsPointLightComponent from '@polyzone/core/src/world/components/PointLightComponent';
 import * as WorldComponentsScriptComponent from '@polyzone/core/src/world/components/ScriptComponent';
 import * as WorldComponents from '@polyzone/core/src/world/components';
+import * as UtilAssetReference from '@polyzone/core/src/util/AssetReference';
 import * as UtilColor3 from '@polyzone/core/src/util/Color3';
 import * as UtilColor4 from '@polyzone/core/src/util/Color4';
 import * as UtilVector2 from '@polyzone/core/src/util/Vector2';
@@ -82,6 +83,10 @@ export const CoreModules: CoreModuleDefinition[] = [
     name: '@polyzone/core/world/components',
     module: WorldComponents,
   },
+  {
+    name: '@polyzone/core/util/AssetReference',
+    module: UtilAssetReference,
+  },
   {
     name: '@polyzone/core/util/Color3',
     module: UtilColor3,
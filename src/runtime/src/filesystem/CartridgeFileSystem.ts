@@ -27,6 +27,10 @@ export class CartridgeFileSystem extends IFileSystem {
     return Promise.resolve(this.readFileSync(path));
   }
 
+  public exists(path: string): boolean {
+    return this.cartridgeData[path] !== undefined;
+  }
+
   public readFileSync(path: string): VirtualFile {
     let fileBytes = this.cartridgeData[path];
     if (!fileBytes) {